@@ -1,62 +1,24 @@
-use std::collections::HashSet;
+use day_04::Scratchcards;
 use std::env;
-use std::fs;
+use std::fs::File;
+use std::io::BufReader;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() != 2 {
         panic!("need argument to filepath")
     }
-    let data = fs::read_to_string(&args[1]).expect("file not present");
+    let file = File::open(&args[1]).expect("file not present");
+    let cards = Scratchcards::from_reader(BufReader::new(file)).expect("invalid card table");
 
-    let solution = get_solution(data);
+    let solution = cards.total_points().expect("points overflowed");
 
     println!("Result is {solution}")
 }
-fn get_solution(data: String) -> u32 {
-    let mut sol: u32 = 0;
-    for line in data.lines() {
-        let (winning_numbers, my_numbers) = get_numbers(&line);
-        // get the intersection of the two sets
-        let match_count = winning_numbers.intersection(&my_numbers).count();
-        if match_count == 0 {
-            continue;
-        } else {
-            sol += 2u32.pow((match_count - 1) as u32);
-        }
-    }
-    sol
-}
-
-type WinningNumbers = HashSet<u32>;
-type MyNumbers = HashSet<u32>;
-
-fn get_numbers(input_line: &str) -> (WinningNumbers, MyNumbers) {
-    let game_line = input_line.split(": ").collect::<Vec<&str>>()[1]
-        .split("| ")
-        .collect::<Vec<&str>>();
-
-    let winning_numbers = game_line[0]
-        .split(" ")
-        .filter_map(|num_str| num_str.trim().parse::<u32>().ok())
-        .collect();
-
-    let my_numbers = game_line[1]
-        .split(" ")
-        .filter_map(|num_str| num_str.trim().parse::<u32>().ok())
-        .collect();
-
-    (winning_numbers, my_numbers)
-}
 
 #[cfg(test)]
 mod tests {
-    use super::get_numbers;
-    use std::collections::HashSet;
-
-    fn make_test_line() -> String {
-        "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53".to_string()
-    }
+    use day_04::Scratchcards;
 
     fn make_test_data() -> String {
         "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
@@ -68,18 +30,13 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
             .to_string()
     }
 
-    #[test]
-    fn test_get_numbers_1() {
-        let test_line = make_test_line();
-        let (winning_numbers, my_numbers) = get_numbers(&test_line);
-        assert_eq!(winning_numbers, HashSet::from([41, 48, 83, 86, 17]));
-        assert_eq!(my_numbers, HashSet::from([83, 86, 6, 31, 17, 9, 48, 53]));
-    }
-
     #[test]
     fn test_solution_example() {
         let test_data = make_test_data();
-        let solution = super::get_solution(test_data);
+        let solution = Scratchcards::parse(&test_data)
+            .unwrap()
+            .total_points()
+            .unwrap();
         assert_eq!(solution, 13);
     }
 }
@@ -1,85 +1,47 @@
-use std::collections::HashSet;
+use day_04::{rule_from_name, Scratchcards};
 use std::env;
-use std::fs;
+use std::fs::File;
+use std::io::BufReader;
 
+// usage: part2 <filepath> [standard|capped|previous|weighted] [--stats]
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
+    if args.len() < 2 {
         panic!("need argument to filepath")
     }
-    let data = fs::read_to_string(&args[1]).expect("file not present");
-
-    let solution = get_solution(data);
-
-    println!("Result is {solution}")
-}
-#[derive(Clone, Debug)]
-struct Card {
-    index: u32,
-    matches: u32,
-}
-fn get_solution(data: String) -> u32 {
-    let mut cards: Vec<Card> = Vec::new();
-    //calculate the number of matches per game
-    for (index, line) in data.lines().enumerate() {
-        let (winning_numbers, my_numbers) = get_numbers(&line);
-        // get the intersection of the two sets
-        let match_count = winning_numbers.intersection(&my_numbers).count();
-        cards.push(Card {
-            index: index as u32,
-            matches: match_count as u32,
-        });
+    let show_stats = args.iter().any(|arg| arg == "--stats");
+    let mut rule_names = args[2..].iter().filter(|arg| *arg != "--stats");
+    let rule_name = rule_names.next().map(String::as_str).unwrap_or("standard");
+    if let Some(extra) = rule_names.next() {
+        panic!("unexpected argument {extra:?}")
     }
-    let max_index = cards.iter().max_by_key(|card| card.index).unwrap().index;
-
-    let mut collected_cards: u32 = 0;
-    let mut cards_to_explore: Vec<u32> = (0..max_index + 1).collect();
-    // iterate through vec while not empty
-    while let Some(index) = cards_to_explore.pop() {
-        // for the number of matches, get teh indeces of the cards to copy
-        let card = cards
-            .get(index as usize)
-            .expect("accessing non existent card");
-
-        let indeces_to_copy = card.index + 1..card.index + 1 + card.matches;
-        // add the card indeces to the cards to explore
-        cards_to_explore.extend(indeces_to_copy);
-        // increment collected cards
-        collected_cards += 1;
+    let rule = rule_from_name(rule_name).expect("unknown copy rule");
+
+    let file = File::open(&args[1]).expect("file not present");
+    let cards = Scratchcards::from_reader(BufReader::new(file)).expect("invalid card table");
+    if show_stats {
+        let stats = cards.cascade(rule.as_ref()).expect("cascade failed");
+        println!("card\tmatches\tpoints\tcopies");
+        for card in stats.iter() {
+            println!(
+                "{}\t{}\t{}\t{}",
+                card.id,
+                card.matches,
+                card.points
+                    .map_or("overflow".to_string(), |points| points.to_string()),
+                card.copies
+            );
+        }
     }
 
-    collected_cards
-}
-
-type WinningNumbers = HashSet<u32>;
-type MyNumbers = HashSet<u32>;
+    let solution = cards.total_cards(rule.as_ref()).expect("cascade failed");
 
-fn get_numbers(input_line: &str) -> (WinningNumbers, MyNumbers) {
-    let game_line = input_line.split(": ").collect::<Vec<&str>>()[1]
-        .split("| ")
-        .collect::<Vec<&str>>();
-
-    let winning_numbers = game_line[0]
-        .split(" ")
-        .filter_map(|num_str| num_str.trim().parse::<u32>().ok())
-        .collect();
-
-    let my_numbers = game_line[1]
-        .split(" ")
-        .filter_map(|num_str| num_str.trim().parse::<u32>().ok())
-        .collect();
-
-    (winning_numbers, my_numbers)
+    println!("Result is {solution}")
 }
 
 #[cfg(test)]
 mod tests {
-    use super::get_numbers;
-    use std::collections::HashSet;
-
-    fn make_test_line() -> String {
-        "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53".to_string()
-    }
+    use day_04::{Scratchcards, Standard};
 
     fn make_test_data() -> String {
         "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
@@ -91,18 +53,11 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
             .to_string()
     }
 
-    #[test]
-    fn test_get_numbers_1() {
-        let test_line = make_test_line();
-        let (winning_numbers, my_numbers) = get_numbers(&test_line);
-        assert_eq!(winning_numbers, HashSet::from([41, 48, 83, 86, 17]));
-        assert_eq!(my_numbers, HashSet::from([83, 86, 6, 31, 17, 9, 48, 53]));
-    }
-
     #[test]
     fn test_solution_example() {
         let test_data = make_test_data();
-        let solution = super::get_solution(test_data);
+        let cards = Scratchcards::parse(&test_data).unwrap();
+        let solution = cards.total_cards(&Standard).unwrap();
         assert_eq!(solution, 30);
     }
 }
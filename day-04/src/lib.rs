@@ -0,0 +1,449 @@
+use std::collections::HashSet;
+use std::io::BufRead;
+
+type WinningNumbers = HashSet<u32>;
+type MyNumbers = HashSet<u32>;
+
+pub fn get_numbers(input_line: &str) -> (WinningNumbers, MyNumbers) {
+    let game_line = input_line.split(": ").collect::<Vec<&str>>()[1]
+        .split("| ")
+        .collect::<Vec<&str>>();
+
+    let winning_numbers = game_line[0]
+        .split(' ')
+        .filter_map(|num_str| num_str.trim().parse::<u32>().ok())
+        .collect();
+
+    let my_numbers = game_line[1]
+        .split(' ')
+        .filter_map(|num_str| num_str.trim().parse::<u32>().ok())
+        .collect();
+
+    (winning_numbers, my_numbers)
+}
+
+/// A scratchcard reduced to what the cascade needs: its number and how many
+/// of my numbers are winning numbers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Card {
+    pub id: u32,
+    pub matches: usize,
+}
+
+impl Card {
+    pub fn parse(line: &str) -> Result<Card, String> {
+        let (header, numbers) = line
+            .split_once(": ")
+            .ok_or_else(|| format!("missing ': ' in card line {line:?}"))?;
+        if !numbers.contains("| ") {
+            return Err(format!("missing '| ' in card line {line:?}"));
+        }
+        let id = header
+            .split_whitespace()
+            .last()
+            .and_then(|id| id.parse::<u32>().ok())
+            .ok_or_else(|| format!("bad card header {header:?}"))?;
+        let (winning_numbers, my_numbers) = get_numbers(line);
+        let matches = winning_numbers.intersection(&my_numbers).count();
+        Ok(Card { id, matches })
+    }
+
+    /// 1 point for the first match, doubled for every match after that
+    pub fn points(&self) -> Result<u64, String> {
+        match self.matches {
+            0 => Ok(0),
+            n => u32::try_from(n - 1)
+                .ok()
+                .and_then(|doublings| 2u64.checked_pow(doublings))
+                .ok_or_else(|| format!("card {} with {n} matches overflows its points", self.id)),
+        }
+    }
+}
+
+/// Decides which cards a single instance of a card wins.
+pub trait CopyRule {
+    /// returns `(target index, copies)` pairs won by one instance of the card at `index`
+    fn wins(
+        &self,
+        index: usize,
+        matches: usize,
+        table_len: usize,
+    ) -> Result<Vec<(usize, u64)>, String>;
+}
+
+/// The puzzle rule: one copy of each of the next `matches` cards.
+/// Winning a card past the end of the table is an error.
+pub struct Standard;
+
+/// Like [`Standard`] but wins past the end of the table are dropped.
+pub struct Capped;
+
+/// One copy of each of the `matches` cards above, stopping at the first card.
+pub struct Previous;
+
+/// Closer cards win more: the k-th card below receives `matches - k + 1` copies.
+/// Wins past the end of the table are dropped.
+pub struct Weighted;
+
+impl CopyRule for Standard {
+    fn wins(
+        &self,
+        index: usize,
+        matches: usize,
+        table_len: usize,
+    ) -> Result<Vec<(usize, u64)>, String> {
+        if index + matches >= table_len {
+            return Err(format!(
+                "card at index {index} with {matches} matches copies past the end of the table"
+            ));
+        }
+        Ok((index + 1..=index + matches).map(|i| (i, 1)).collect())
+    }
+}
+
+impl CopyRule for Capped {
+    fn wins(
+        &self,
+        index: usize,
+        matches: usize,
+        table_len: usize,
+    ) -> Result<Vec<(usize, u64)>, String> {
+        let end = (index + matches).min(table_len - 1);
+        Ok((index + 1..=end).map(|i| (i, 1)).collect())
+    }
+}
+
+impl CopyRule for Previous {
+    fn wins(
+        &self,
+        index: usize,
+        matches: usize,
+        _table_len: usize,
+    ) -> Result<Vec<(usize, u64)>, String> {
+        let start = index.saturating_sub(matches);
+        Ok((start..index).map(|i| (i, 1)).collect())
+    }
+}
+
+impl CopyRule for Weighted {
+    fn wins(
+        &self,
+        index: usize,
+        matches: usize,
+        table_len: usize,
+    ) -> Result<Vec<(usize, u64)>, String> {
+        Ok((1..=matches)
+            .map(|k| (index + k, (matches - k + 1) as u64))
+            .take_while(|(i, _)| *i < table_len)
+            .collect())
+    }
+}
+
+pub fn rule_from_name(name: &str) -> Option<Box<dyn CopyRule>> {
+    match name {
+        "standard" => Some(Box::new(Standard)),
+        "capped" => Some(Box::new(Capped)),
+        "previous" => Some(Box::new(Previous)),
+        "weighted" => Some(Box::new(Weighted)),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CardStats {
+    pub id: u32,
+    pub matches: usize,
+    // None if the points don't fit in a u64, copies don't depend on them
+    pub points: Option<u64>,
+    // copies won, not counting the original card
+    pub copies: u64,
+}
+
+impl CardStats {
+    pub fn instances(&self) -> u64 {
+        self.copies + 1
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Scratchcards {
+    cards: Vec<Card>,
+}
+
+impl Scratchcards {
+    pub fn parse(input: &str) -> Result<Scratchcards, String> {
+        let cards = input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(Card::parse)
+            .collect::<Result<Vec<Card>, String>>()?;
+        Ok(Scratchcards { cards })
+    }
+
+    /// Reads one line at a time into a reused buffer, so only the match
+    /// counts are kept in memory.
+    pub fn from_reader<R: BufRead>(mut reader: R) -> Result<Scratchcards, String> {
+        let mut cards = Vec::new();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader
+                .read_line(&mut line)
+                .map_err(|err| format!("failed to read card: {err}"))?;
+            if read == 0 {
+                break;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            cards.push(Card::parse(line)?);
+        }
+        Ok(Scratchcards { cards })
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn total_points(&self) -> Result<u64, String> {
+        self.cards.iter().try_fold(0u64, |total, card| {
+            total
+                .checked_add(card.points()?)
+                .ok_or_else(|| "total points overflowed".to_string())
+        })
+    }
+
+    /// Plays out every original and won card under `rule`.
+    /// Cards are processed in topological order of the win graph, so a rule
+    /// may copy cards in any direction as long as no card ends up winning itself.
+    pub fn cascade(&self, rule: &dyn CopyRule) -> Result<Vec<CardStats>, String> {
+        let n = self.cards.len();
+        let mut wins: Vec<Vec<(usize, u64)>> = Vec::with_capacity(n);
+        let mut in_degree = vec![0usize; n];
+        for (index, card) in self.cards.iter().enumerate() {
+            let won = rule
+                .wins(index, card.matches, n)?
+                .into_iter()
+                .filter(|(_, copies)| *copies > 0)
+                .collect::<Vec<_>>();
+            for (target, _) in won.iter() {
+                if *target >= n {
+                    return Err(format!(
+                        "card at index {index} won non existent card at index {target}"
+                    ));
+                }
+                in_degree[*target] += 1;
+            }
+            wins.push(won);
+        }
+
+        let mut instances = vec![1u64; n];
+        let mut to_process: Vec<usize> = (0..n).rev().filter(|i| in_degree[*i] == 0).collect();
+        let mut processed = 0;
+        while let Some(index) = to_process.pop() {
+            processed += 1;
+            for (target, copies) in wins[index].iter() {
+                instances[*target] = copies
+                    .checked_mul(instances[index])
+                    .and_then(|won| won.checked_add(instances[*target]))
+                    .ok_or_else(|| format!("card count overflowed at index {target}"))?;
+                in_degree[*target] -= 1;
+                if in_degree[*target] == 0 {
+                    to_process.push(*target);
+                }
+            }
+        }
+        if processed != n {
+            return Err("copy rule makes cards win copies of themselves".to_string());
+        }
+
+        Ok(self
+            .cards
+            .iter()
+            .zip(instances)
+            .map(|(card, instances)| CardStats {
+                id: card.id,
+                matches: card.matches,
+                points: card.points().ok(),
+                copies: instances - 1,
+            })
+            .collect())
+    }
+
+    pub fn total_cards(&self, rule: &dyn CopyRule) -> Result<u64, String> {
+        self.cascade(rule)?.iter().try_fold(0u64, |total, card| {
+            total
+                .checked_add(card.instances())
+                .ok_or_else(|| "card count overflowed".to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_test_line() -> String {
+        "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53".to_string()
+    }
+
+    fn make_test_data() -> String {
+        "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
+            .to_string()
+    }
+
+    fn instances(stats: &[CardStats]) -> Vec<u64> {
+        stats.iter().map(CardStats::instances).collect()
+    }
+
+    #[test]
+    fn test_get_numbers_1() {
+        let test_line = make_test_line();
+        let (winning_numbers, my_numbers) = get_numbers(&test_line);
+        assert_eq!(winning_numbers, HashSet::from([41, 48, 83, 86, 17]));
+        assert_eq!(my_numbers, HashSet::from([83, 86, 6, 31, 17, 9, 48, 53]));
+    }
+
+    #[test]
+    fn test_card_parse() {
+        let card = Card::parse("Card  12: 41 48 83 86 17 | 83 86  6 31 17  9 48 53").unwrap();
+        assert_eq!(card, Card { id: 12, matches: 4 });
+        assert_eq!(card.points(), Ok(8));
+        assert!(Card::parse("Card 1 41 48 | 83").is_err());
+        assert!(Card::parse("Card x: 41 48 | 83").is_err());
+    }
+
+    #[test]
+    fn test_points_overflow() {
+        let card = Card { id: 1, matches: 64 };
+        assert_eq!(card.points(), Ok(1 << 63));
+        let card = Card { id: 2, matches: 65 };
+        assert!(card.points().is_err());
+        let cards = Scratchcards {
+            cards: vec![card, Card { id: 3, matches: 0 }],
+        };
+        assert!(cards.total_points().is_err());
+        // copies don't need the points, so the cascade still works
+        let stats = cards.cascade(&Capped).unwrap();
+        assert_eq!(stats[0].points, None);
+        assert_eq!(cards.total_cards(&Capped), Ok(3));
+    }
+
+    #[test]
+    fn test_total_cards_overflow() {
+        // each of the first 64 cards wins a copy of every card after it up to
+        // the 64th, so card k ends up with 2^k instances and they add up to
+        // 2^64 - 1, then one more card tips the total over
+        let mut cards = (0..64)
+            .map(|i| Card {
+                id: i + 1,
+                matches: 63 - i as usize,
+            })
+            .collect::<Vec<_>>();
+        cards.push(Card { id: 65, matches: 0 });
+        let cards = Scratchcards { cards };
+        assert_eq!(
+            cards.total_cards(&Capped),
+            Err("card count overflowed".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_reader_matches_parse() {
+        let test_data = make_test_data();
+        let streamed = Scratchcards::from_reader(test_data.as_bytes()).unwrap();
+        let parsed = Scratchcards::parse(&test_data).unwrap();
+        assert_eq!(streamed.cards(), parsed.cards());
+        assert_eq!(streamed.total_points(), Ok(13));
+    }
+
+    #[test]
+    fn test_standard_rule() {
+        let cards = Scratchcards::parse(&make_test_data()).unwrap();
+        let stats = cards.cascade(&Standard).unwrap();
+        assert_eq!(instances(&stats), vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(
+            stats[2],
+            CardStats {
+                id: 3,
+                matches: 2,
+                points: Some(2),
+                copies: 3
+            }
+        );
+        assert_eq!(cards.total_cards(&Standard).unwrap(), 30);
+    }
+
+    #[test]
+    fn test_standard_rule_past_end() {
+        let test_data = make_test_data()
+            .lines()
+            .take(3)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let cards = Scratchcards::parse(&test_data).unwrap();
+        assert!(cards.cascade(&Standard).is_err());
+    }
+
+    #[test]
+    fn test_capped_rule() {
+        let test_data = make_test_data()
+            .lines()
+            .take(3)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let cards = Scratchcards::parse(&test_data).unwrap();
+        let stats = cards.cascade(&Capped).unwrap();
+        assert_eq!(instances(&stats), vec![1, 2, 4]);
+        // on the full table nothing reaches the end, so capping changes nothing
+        let cards = Scratchcards::parse(&make_test_data()).unwrap();
+        assert_eq!(cards.total_cards(&Capped).unwrap(), 30);
+    }
+
+    #[test]
+    fn test_previous_rule() {
+        let cards = Scratchcards::parse(&make_test_data()).unwrap();
+        let stats = cards.cascade(&Previous).unwrap();
+        assert_eq!(instances(&stats), vec![6, 3, 2, 1, 1, 1]);
+        assert_eq!(cards.total_cards(&Previous).unwrap(), 14);
+    }
+
+    #[test]
+    fn test_weighted_rule() {
+        let cards = Scratchcards::parse(&make_test_data()).unwrap();
+        let stats = cards.cascade(&Weighted).unwrap();
+        assert_eq!(instances(&stats), vec![1, 5, 14, 36, 52, 1]);
+        assert_eq!(cards.total_cards(&Weighted).unwrap(), 109);
+    }
+
+    #[test]
+    fn test_cyclic_rule() {
+        struct Both;
+        impl CopyRule for Both {
+            fn wins(
+                &self,
+                index: usize,
+                matches: usize,
+                table_len: usize,
+            ) -> Result<Vec<(usize, u64)>, String> {
+                let mut won = Previous.wins(index, matches, table_len)?;
+                won.extend(Capped.wins(index, matches, table_len)?);
+                Ok(won)
+            }
+        }
+        let cards = Scratchcards::parse(&make_test_data()).unwrap();
+        assert!(cards.cascade(&Both).is_err());
+    }
+
+    #[test]
+    fn test_rule_from_name() {
+        assert!(rule_from_name("capped").is_some());
+        assert!(rule_from_name("sideways").is_none());
+    }
+}
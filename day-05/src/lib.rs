@@ -0,0 +1,935 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Range;
+
+pub type Seeds = Vec<usize>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlmanacError {
+    Syntax {
+        line: usize,
+        message: String,
+    },
+    Overlap {
+        map: String,
+        first: Range<usize>,
+        second: Range<usize>,
+    },
+    // two maps convert from the same category
+    DuplicateSource {
+        category: String,
+    },
+    // the chain stops at a category with no map out of it
+    BrokenChain {
+        category: String,
+    },
+    // following the chain comes back to a category already visited
+    Cycle {
+        category: String,
+    },
+    // a map that the chain never uses
+    Unreachable {
+        map: String,
+    },
+    // a range whose source or destination runs past usize::MAX
+    RangeOverflow {
+        map: String,
+        dest_start: usize,
+        source_start: usize,
+        length: usize,
+    },
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::Syntax { line, message } => write!(f, "line {line}: {message}"),
+            AlmanacError::Overlap { map, first, second } => {
+                write!(f, "{map}: source ranges {first:?} and {second:?} overlap")
+            }
+            AlmanacError::DuplicateSource { category } => {
+                write!(f, "more than one map converts from {category}")
+            }
+            AlmanacError::BrokenChain { category } => {
+                write!(f, "no map converts from {category}")
+            }
+            AlmanacError::Cycle { category } => {
+                write!(f, "the chain returns to {category}")
+            }
+            AlmanacError::Unreachable { map } => write!(f, "{map} is never used"),
+            AlmanacError::RangeOverflow {
+                map,
+                dest_start,
+                source_start,
+                length,
+            } => write!(
+                f,
+                "{map}: range {dest_start} {source_start} {length} runs past {}",
+                usize::MAX
+            ),
+        }
+    }
+}
+
+/// A `Map` together with the categories named in its header.
+#[derive(Debug)]
+pub struct CategoryMap {
+    pub source: String,
+    pub dest: String,
+    pub map: Map,
+}
+
+impl CategoryMap {
+    pub fn name(&self) -> String {
+        format!("{}-to-{}", self.source, self.dest)
+    }
+}
+
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Seeds,
+    pub maps: Vec<CategoryMap>,
+}
+
+fn parse_numbers(line: &str, line_index: usize) -> Result<Vec<usize>, AlmanacError> {
+    line.split_whitespace()
+        .map(|num_str| {
+            num_str.parse::<usize>().map_err(|_| AlmanacError::Syntax {
+                line: line_index + 1,
+                message: format!("{num_str:?} is not a number"),
+            })
+        })
+        .collect()
+}
+
+/// Reads the almanac block by block, using the `<source>-to-<dest> map:`
+/// headers rather than the block order.
+pub fn parse_almanac(data: &str) -> Result<Almanac, AlmanacError> {
+    let syntax = |line: usize, message: &str| AlmanacError::Syntax {
+        line: line + 1,
+        message: message.to_string(),
+    };
+    let mut lines = data.lines().enumerate();
+    let seeds = match lines.next() {
+        Some((index, line)) => match line.strip_prefix("seeds:") {
+            Some(seeds) => parse_numbers(seeds, index)?,
+            None => return Err(syntax(index, "expected the seeds line")),
+        },
+        None => return Err(syntax(0, "empty almanac")),
+    };
+
+    let mut maps: Vec<CategoryMap> = Vec::new();
+    for (index, line) in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(header) = line.strip_suffix(" map:") {
+            let (source, dest) = header
+                .split_once("-to-")
+                .ok_or_else(|| syntax(index, "map header should be <source>-to-<dest> map:"))?;
+            maps.push(CategoryMap {
+                source: source.to_string(),
+                dest: dest.to_string(),
+                map: Map::new(Vec::new()),
+            });
+            continue;
+        }
+        let numbers = parse_numbers(line, index)?;
+        if numbers.len() != 3 {
+            return Err(syntax(index, "map ranges need exactly 3 numbers"));
+        }
+        let current = maps
+            .last_mut()
+            .ok_or_else(|| syntax(index, "map range before any map header"))?;
+        current
+            .map
+            .ranges
+            .push(MapRange::new(numbers[0], numbers[1], numbers[2]));
+    }
+    Ok(Almanac { seeds, maps })
+}
+
+impl Almanac {
+    /// Follows maps from `from` until `to` or until the chain can't continue,
+    /// returning the maps used so far and why it stopped early.
+    fn walk(&self, from: &str, to: &str) -> (Vec<&CategoryMap>, Option<AlmanacError>) {
+        let mut chain = Vec::new();
+        let mut visited = HashSet::from([from]);
+        let mut category = from;
+        while category != to {
+            let mut outgoing = self.maps.iter().filter(|map| map.source == category);
+            let Some(next) = outgoing.next() else {
+                let error = AlmanacError::BrokenChain {
+                    category: category.to_string(),
+                };
+                return (chain, Some(error));
+            };
+            if outgoing.next().is_some() {
+                let error = AlmanacError::DuplicateSource {
+                    category: category.to_string(),
+                };
+                return (chain, Some(error));
+            }
+            chain.push(next);
+            if !visited.insert(&next.dest) {
+                let error = AlmanacError::Cycle {
+                    category: next.dest.clone(),
+                };
+                return (chain, Some(error));
+            }
+            category = &next.dest;
+        }
+        (chain, None)
+    }
+
+    /// The maps to apply, in order, to get from `from` to `to`.
+    pub fn chain(&self, from: &str, to: &str) -> Result<Vec<&CategoryMap>, AlmanacError> {
+        match self.walk(from, to) {
+            (chain, None) => Ok(chain),
+            (_, Some(error)) => Err(error),
+        }
+    }
+
+    /// Everything that would make the seed to location answer wrong.
+    /// Gaps between ranges are fine, they map to themselves.
+    pub fn validate(&self) -> Vec<AlmanacError> {
+        let mut errors = Vec::new();
+        for map in self.maps.iter() {
+            errors.extend(
+                map.map
+                    .overflowing()
+                    .map(|range| AlmanacError::RangeOverflow {
+                        map: map.name(),
+                        dest_start: range.dest_start,
+                        source_start: range.source_start,
+                        length: range.length,
+                    }),
+            );
+            errors.extend(map.map.overlaps().into_iter().map(|(first, second)| {
+                AlmanacError::Overlap {
+                    map: map.name(),
+                    first,
+                    second,
+                }
+            }));
+        }
+        let mut sources = HashSet::new();
+        for map in self.maps.iter() {
+            if !sources.insert(&map.source) {
+                errors.push(AlmanacError::DuplicateSource {
+                    category: map.source.clone(),
+                });
+            }
+        }
+        let (chain, stopped) = self.walk("seed", "location");
+        match stopped {
+            // already reported above, and we can't tell which branch was meant
+            Some(AlmanacError::DuplicateSource { .. }) => return errors,
+            Some(error) => errors.push(error),
+            None => {}
+        }
+        errors.extend(
+            self.maps
+                .iter()
+                .filter(|map| !chain.iter().any(|used| std::ptr::eq(*used, *map)))
+                .map(|map| AlmanacError::Unreachable { map: map.name() }),
+        );
+        errors
+    }
+
+    pub fn map_series(&self) -> Result<MapSeries, AlmanacError> {
+        let chain = self.chain("seed", "location")?;
+        Ok(MapSeries::new(
+            chain.into_iter().map(|map| map.map.clone()).collect(),
+        ))
+    }
+}
+
+pub fn parse_input(data: &str) -> Result<(Seeds, MapSeries), Vec<AlmanacError>> {
+    let almanac = parse_almanac(data).map_err(|error| vec![error])?;
+    let errors = almanac.validate();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let map_series = almanac.map_series().map_err(|error| vec![error])?;
+    Ok((almanac.seeds, map_series))
+}
+
+/// Part 2 reads the seeds line as `(start, length)` pairs.
+pub fn seed_ranges(seeds: &[usize]) -> Result<Vec<Range<usize>>, AlmanacError> {
+    let syntax = |message: String| AlmanacError::Syntax { line: 1, message };
+    if !seeds.len().is_multiple_of(2) {
+        return Err(syntax(format!(
+            "{} seed numbers can't be read as (start, length) pairs",
+            seeds.len()
+        )));
+    }
+    seeds
+        .chunks(2)
+        .map(|chunk| match chunk[0].checked_add(chunk[1]) {
+            Some(end) => Ok(chunk[0]..end),
+            None => Err(syntax(format!(
+                "seed range {} + {} is too long",
+                chunk[0], chunk[1]
+            ))),
+        })
+        .collect()
+}
+
+// let's store a map_range as a struct:
+#[derive(Debug, Clone)]
+pub struct MapRange {
+    dest_start: usize,
+    source_start: usize,
+    length: usize,
+}
+
+impl MapRange {
+    pub fn new(dest_start: usize, source_start: usize, length: usize) -> Self {
+        Self {
+            dest_start,
+            source_start,
+            length,
+        }
+    }
+
+    /// Whether both the source and destination end fit in a usize. Ranges
+    /// that don't are reported by `Almanac::validate` and ignored by lookups.
+    fn fits(&self) -> bool {
+        self.source_start.checked_add(self.length).is_some()
+            && self.dest_start.checked_add(self.length).is_some()
+    }
+
+    fn in_range(&self, source_index: &usize) -> bool {
+        source_index
+            .checked_sub(self.source_start)
+            .is_some_and(|distance| distance < self.length)
+    }
+
+    fn map(&self, source_index: usize) -> usize {
+        let distance = source_index - self.source_start;
+        self.dest_start + distance
+    }
+
+    fn source_end(&self) -> usize {
+        self.source_start + self.length
+    }
+
+    /// Splits `range` into the part this map range covers (already mapped to
+    /// the destination) and the uncovered parts before and after it.
+    fn split(&self, range: Range<usize>) -> (Option<Range<usize>>, Vec<Range<usize>>) {
+        let overlap_start = range.start.max(self.source_start);
+        let overlap_end = range.end.min(self.source_end());
+        if overlap_start >= overlap_end {
+            return (None, vec![range]);
+        }
+        let mut rest = Vec::new();
+        if range.start < overlap_start {
+            rest.push(range.start..overlap_start);
+        }
+        if overlap_end < range.end {
+            rest.push(overlap_end..range.end);
+        }
+        let mapped_start = self.map(overlap_start);
+        let mapped = mapped_start..mapped_start + (overlap_end - overlap_start);
+        (Some(mapped), rest)
+    }
+}
+
+// make a map a collection of MapRanges
+#[derive(Debug, Clone)]
+pub struct Map {
+    ranges: Vec<MapRange>,
+}
+
+impl Map {
+    pub fn new(ranges: Vec<MapRange>) -> Self {
+        Self { ranges }
+    }
+
+    /// The ranges that lookups use, leaving out the ones that overflow.
+    fn fitting(&self) -> impl Iterator<Item = &MapRange> {
+        self.ranges.iter().filter(|range| range.fits())
+    }
+
+    /// Ranges whose source or destination end overflows a usize.
+    pub fn overflowing(&self) -> impl Iterator<Item = &MapRange> {
+        self.ranges.iter().filter(|range| !range.fits())
+    }
+
+    pub fn map(&self, source_index: usize) -> usize {
+        match self.fitting().find(|range| range.in_range(&source_index)) {
+            Some(range) => range.map(source_index),
+            None => source_index,
+        }
+    }
+
+    /// Pairs of source ranges that share at least one index.
+    pub fn overlaps(&self) -> Vec<(Range<usize>, Range<usize>)> {
+        let mut sources = self
+            .fitting()
+            .map(|range| range.source_start..range.source_end())
+            .collect::<Vec<_>>();
+        sources.sort_by_key(|range| range.start);
+        let mut overlaps = Vec::new();
+        for (i, first) in sources.iter().enumerate() {
+            for second in sources[i + 1..]
+                .iter()
+                .take_while(|second| second.start < first.end)
+            {
+                overlaps.push((first.clone(), second.clone()));
+            }
+        }
+        overlaps
+    }
+
+    /// Source indices between the lowest and highest range that no range
+    /// covers. These map to themselves.
+    pub fn gaps(&self) -> Vec<Range<usize>> {
+        let covered = merge_ranges(
+            self.fitting()
+                .map(|range| range.source_start..range.source_end())
+                .collect(),
+        );
+        covered
+            .windows(2)
+            .map(|pair| pair[0].end..pair[1].start)
+            .collect()
+    }
+
+    /// Maps a whole interval at once. Parts not covered by any `MapRange`
+    /// map to themselves, same as for single indices.
+    pub fn map_range(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut mapped = Vec::new();
+        let mut unmapped = vec![range];
+        for map_range in self.fitting() {
+            let mut remaining = Vec::new();
+            for range in unmapped {
+                let (hit, rest) = map_range.split(range);
+                mapped.extend(hit);
+                remaining.extend(rest);
+            }
+            unmapped = remaining;
+        }
+        mapped.extend(unmapped);
+        mapped
+    }
+}
+
+// make a MapSeries a sequence of Maps
+#[derive(Debug)]
+pub struct MapSeries {
+    maps: Vec<Map>,
+}
+
+impl MapSeries {
+    pub fn new(maps: Vec<Map>) -> Self {
+        Self { maps }
+    }
+
+    pub fn map(&self, source_index: usize) -> usize {
+        self.maps
+            .iter()
+            .fold(source_index, |index, map| map.map(index))
+    }
+
+    /// Flattens the whole series into one seed to location function.
+    pub fn compile(&self) -> PiecewiseMap {
+        self.maps
+            .iter()
+            .fold(PiecewiseMap::identity(), |compiled, map| {
+                compiled.then(&PiecewiseMap::from_map(map))
+            })
+    }
+
+    /// Pushes intervals through every map without expanding them, merging
+    /// touching intervals after each step so the count stays small.
+    pub fn map_ranges(&self, ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+        self.maps.iter().fold(merge_ranges(ranges), |ranges, map| {
+            merge_ranges(
+                ranges
+                    .into_iter()
+                    .flat_map(|range| map.map_range(range))
+                    .collect(),
+            )
+        })
+    }
+}
+
+/// One linear piece of a compiled map: every index in `source` is shifted so
+/// that `source.start` lands on `dest_start`. Pieces built from a `Map` only
+/// come from ranges that fit, so their destination fits too.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Piece {
+    pub source: Range<usize>,
+    pub dest_start: usize,
+}
+
+impl Piece {
+    fn map(&self, source_index: usize) -> usize {
+        self.dest_start + (source_index - self.source.start)
+    }
+
+    pub fn dest(&self) -> Range<usize> {
+        let end = self
+            .dest_start
+            .checked_add(self.source.len())
+            .expect("piece destination runs past usize::MAX");
+        self.dest_start..end
+    }
+}
+
+/// A whole `Map` or `MapSeries` flattened into sorted, non overlapping pieces
+/// covering `0..usize::MAX`, so a lookup is a single binary search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PiecewiseMap {
+    pieces: Vec<Piece>,
+    // indices into pieces, sorted by dest_start, for inverse lookups
+    by_dest: Vec<usize>,
+}
+
+impl PiecewiseMap {
+    pub fn identity() -> Self {
+        Self::from_pieces(vec![Piece {
+            source: 0..usize::MAX,
+            dest_start: 0,
+        }])
+    }
+
+    /// Keeps `Map::map` semantics: where ranges overlap the first one listed wins.
+    pub fn from_map(map: &Map) -> Self {
+        let mut bounds = vec![0, usize::MAX];
+        for range in map.fitting() {
+            bounds.push(range.source_start);
+            bounds.push(range.source_end());
+        }
+        bounds.sort();
+        bounds.dedup();
+
+        let pieces = bounds
+            .windows(2)
+            .map(|bound| Piece {
+                source: bound[0]..bound[1],
+                dest_start: map.map(bound[0]),
+            })
+            .collect();
+        Self::from_pieces(pieces)
+    }
+
+    fn from_pieces(pieces: Vec<Piece>) -> Self {
+        // join neighbours that continue the same line
+        let mut merged: Vec<Piece> = Vec::with_capacity(pieces.len());
+        for piece in pieces.into_iter().filter(|piece| !piece.source.is_empty()) {
+            match merged.last_mut() {
+                Some(last)
+                    if last.source.end == piece.source.start
+                        && last.dest().end == piece.dest_start =>
+                {
+                    last.source.end = piece.source.end
+                }
+                _ => merged.push(piece),
+            }
+        }
+        let mut by_dest = (0..merged.len()).collect::<Vec<usize>>();
+        by_dest.sort_by_key(|index| merged[*index].dest_start);
+        Self {
+            pieces: merged,
+            by_dest,
+        }
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    fn piece_index(&self, source_index: usize) -> usize {
+        self.pieces
+            .partition_point(|piece| piece.source.end <= source_index)
+    }
+
+    pub fn map(&self, source_index: usize) -> usize {
+        // the pieces stop just short of usize::MAX, which no range can move
+        match self.pieces.get(self.piece_index(source_index)) {
+            Some(piece) => piece.map(source_index),
+            None => source_index,
+        }
+    }
+
+    /// `self` followed by `next`.
+    pub fn then(&self, next: &PiecewiseMap) -> PiecewiseMap {
+        let mut pieces = Vec::new();
+        for piece in self.pieces.iter() {
+            let dest = piece.dest();
+            let first = next.piece_index(dest.start);
+            for next_piece in next.pieces[first..]
+                .iter()
+                .take_while(|next_piece| next_piece.source.start < dest.end)
+            {
+                let start = dest.start.max(next_piece.source.start);
+                let end = dest.end.min(next_piece.source.end);
+                let source_start = piece.source.start + (start - dest.start);
+                pieces.push(Piece {
+                    source: source_start..source_start + (end - start),
+                    dest_start: next_piece.map(start),
+                });
+            }
+        }
+        Self::from_pieces(pieces)
+    }
+
+    /// Every source index that maps to `dest_index`, smallest first.
+    pub fn inverse(&self, dest_index: usize) -> Vec<usize> {
+        let candidates = self
+            .by_dest
+            .partition_point(|index| self.pieces[*index].dest_start <= dest_index);
+        let mut sources = self.by_dest[..candidates]
+            .iter()
+            .map(|index| &self.pieces[*index])
+            .filter(|piece| piece.dest().contains(&dest_index))
+            .map(|piece| piece.source.start + (dest_index - piece.dest_start))
+            .collect::<Vec<usize>>();
+        if dest_index == usize::MAX {
+            sources.push(dest_index);
+        }
+        sources.sort();
+        sources
+    }
+
+    /// The source index within `ranges` with the lowest destination, as
+    /// `(source, destination)`. Only piece starts need checking since each
+    /// piece is increasing.
+    pub fn lowest(&self, ranges: &[Range<usize>]) -> Option<(usize, usize)> {
+        ranges
+            .iter()
+            .filter(|range| !range.is_empty())
+            .flat_map(|range| {
+                let first = self.piece_index(range.start);
+                self.pieces[first..]
+                    .iter()
+                    .take_while(move |piece| piece.source.start < range.end)
+                    .map(move |piece| {
+                        let source = range.start.max(piece.source.start);
+                        (source, piece.map(source))
+                    })
+            })
+            .min_by_key(|(source, dest)| (*dest, *source))
+    }
+}
+
+/// Sorts ranges and joins the ones that overlap or touch. Empty ranges are dropped.
+pub fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_test_data() -> String {
+        "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4"
+            .to_string()
+    }
+
+    #[test]
+    fn test_map_single_seeds() {
+        let (seeds, map_series) = parse_input(&make_test_data()).unwrap();
+        let locations = seeds
+            .iter()
+            .map(|seed| map_series.map(*seed))
+            .collect::<Vec<usize>>();
+        assert_eq!(locations, vec![82, 43, 86, 35]);
+    }
+
+    #[test]
+    fn test_map_range_split() {
+        let map = Map::new(vec![MapRange::new(50, 98, 2), MapRange::new(52, 50, 48)]);
+        let mut mapped = map.map_range(40..100);
+        mapped.sort_by_key(|range| range.start);
+        // 40..50 passes through, 50..98 shifts up by 2, 98..100 wraps down to 50
+        assert_eq!(mapped, vec![40..50, 50..52, 52..100]);
+    }
+
+    #[test]
+    fn test_map_ranges_matches_brute_force() {
+        let (seeds, map_series) = parse_input(&make_test_data()).unwrap();
+        let ranges = seed_ranges(&seeds).unwrap();
+        let mut expected = ranges
+            .iter()
+            .flat_map(|range| range.clone().map(|seed| map_series.map(seed)))
+            .collect::<Vec<usize>>();
+        expected.sort();
+        let mut actual = map_series
+            .map_ranges(ranges)
+            .into_iter()
+            .flatten()
+            .collect::<Vec<usize>>();
+        actual.sort();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_lowest_location_of_ranges() {
+        let (seeds, map_series) = parse_input(&make_test_data()).unwrap();
+        let lowest = map_series.map_ranges(seed_ranges(&seeds).unwrap())[0].start;
+        assert_eq!(lowest, 46);
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        assert_eq!(
+            merge_ranges(vec![5..8, 0..2, 2..3, 7..10, 4..4]),
+            vec![0..3, 5..10]
+        );
+    }
+
+    #[test]
+    fn test_piecewise_map_matches_map() {
+        let map = Map::new(vec![MapRange::new(50, 98, 2), MapRange::new(52, 50, 48)]);
+        let compiled = PiecewiseMap::from_map(&map);
+        // identity below 50, shift by 2 up to 98, then down to 50
+        assert_eq!(compiled.pieces().len(), 4);
+        for index in 0..150 {
+            assert_eq!(compiled.map(index), map.map(index));
+        }
+    }
+
+    #[test]
+    fn test_compiled_series_matches_series() {
+        let (_, map_series) = parse_input(&make_test_data()).unwrap();
+        let compiled = map_series.compile();
+        for seed in 0..200 {
+            assert_eq!(compiled.map(seed), map_series.map(seed));
+        }
+        assert_eq!(compiled.map(usize::MAX - 1), usize::MAX - 1);
+        assert_eq!(compiled.map(usize::MAX), usize::MAX);
+        assert_eq!(compiled.inverse(usize::MAX), vec![usize::MAX]);
+        assert_eq!(PiecewiseMap::identity().map(usize::MAX), usize::MAX);
+    }
+
+    #[test]
+    fn test_bad_seed_ranges() {
+        assert_eq!(seed_ranges(&[79, 14, 55, 13]), Ok(vec![79..93, 55..68]));
+        assert!(matches!(
+            seed_ranges(&[79, 14, 55]),
+            Err(AlmanacError::Syntax { line: 1, .. })
+        ));
+        assert!(seed_ranges(&[usize::MAX, 1]).is_err());
+    }
+
+    #[test]
+    fn test_compiled_inverse() {
+        let (_, map_series) = parse_input(&make_test_data()).unwrap();
+        let compiled = map_series.compile();
+        for location in 0..200 {
+            let seeds = compiled.inverse(location);
+            let expected = (0..200)
+                .filter(|seed| map_series.map(*seed) == location)
+                .collect::<Vec<usize>>();
+            // seeds above 200 can land below 200, so only check the ones we tried
+            let in_range = seeds
+                .iter()
+                .copied()
+                .filter(|seed| *seed < 200)
+                .collect::<Vec<_>>();
+            assert_eq!(in_range, expected);
+            for seed in seeds {
+                assert_eq!(map_series.map(seed), location);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compiled_lowest() {
+        let (seeds, map_series) = parse_input(&make_test_data()).unwrap();
+        let compiled = map_series.compile();
+        let single_seeds = seeds
+            .iter()
+            .map(|seed| *seed..*seed + 1)
+            .collect::<Vec<_>>();
+        assert_eq!(compiled.lowest(&single_seeds), Some((13, 35)));
+        assert_eq!(
+            compiled.lowest(&seed_ranges(&seeds).unwrap()),
+            Some((82, 46))
+        );
+        assert_eq!(compiled.lowest(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_out_of_order() {
+        let test_data = make_test_data();
+        let mut blocks = test_data.split("\n\n").collect::<Vec<&str>>();
+        blocks[1..].reverse();
+        let shuffled = blocks.join("\n\n");
+        let (seeds, map_series) = parse_input(&shuffled).unwrap();
+        let locations = seeds
+            .iter()
+            .map(|seed| map_series.map(*seed))
+            .collect::<Vec<usize>>();
+        assert_eq!(locations, vec![82, 43, 86, 35]);
+    }
+
+    #[test]
+    fn test_validate_overlap() {
+        let test_data = make_test_data().replace("52 50 48", "52 50 49");
+        let errors = parse_input(&test_data).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![AlmanacError::Overlap {
+                map: "seed-to-soil".to_string(),
+                first: 50..99,
+                second: 98..100,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_broken_chain() {
+        let test_data = make_test_data().replace("water-to-light", "water-to-lamp");
+        let errors = parse_input(&test_data).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                AlmanacError::BrokenChain {
+                    category: "lamp".to_string()
+                },
+                AlmanacError::Unreachable {
+                    map: "light-to-temperature".to_string()
+                },
+                AlmanacError::Unreachable {
+                    map: "temperature-to-humidity".to_string()
+                },
+                AlmanacError::Unreachable {
+                    map: "humidity-to-location".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_unreachable() {
+        let test_data = make_test_data() + "\n\nwater-to-ice map:\n1 2 3";
+        let almanac = parse_almanac(&test_data).unwrap();
+        assert_eq!(
+            almanac.validate(),
+            vec![AlmanacError::DuplicateSource {
+                category: "water".to_string()
+            }]
+        );
+        let test_data = make_test_data() + "\n\nice-to-snow map:\n1 2 3";
+        let almanac = parse_almanac(&test_data).unwrap();
+        assert_eq!(
+            almanac.validate(),
+            vec![AlmanacError::Unreachable {
+                map: "ice-to-snow".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_cycle() {
+        let test_data = make_test_data().replace("humidity-to-location", "humidity-to-soil");
+        let errors = parse_input(&test_data).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![AlmanacError::Cycle {
+                category: "soil".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert!(matches!(
+            parse_almanac("seeds: 1 x"),
+            Err(AlmanacError::Syntax { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_almanac("seeds: 1 2\n\nseed-to-soil map:\n1 2"),
+            Err(AlmanacError::Syntax { line: 4, .. })
+        ));
+        assert!(matches!(
+            parse_almanac("seeds: 1 2\n\n1 2 3"),
+            Err(AlmanacError::Syntax { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_overflow() {
+        let test_data = make_test_data().replace("50 98 2", "100 18446744073709551610 10");
+        let errors = parse_input(&test_data).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![AlmanacError::RangeOverflow {
+                map: "seed-to-soil".to_string(),
+                dest_start: 100,
+                source_start: 18446744073709551610,
+                length: 10,
+            }]
+        );
+        let almanac = parse_almanac(&test_data).unwrap();
+        let map = &almanac.maps[0].map;
+        // the range is left out of lookups rather than wrapping
+        assert_eq!(map.map(usize::MAX - 1), usize::MAX - 1);
+        assert_eq!(
+            map.map_range(usize::MAX - 2..usize::MAX),
+            vec![usize::MAX - 2..usize::MAX]
+        );
+        assert_eq!(
+            PiecewiseMap::from_map(map).map(usize::MAX - 1),
+            usize::MAX - 1
+        );
+
+        let dest_overflow = Map::new(vec![MapRange::new(usize::MAX - 1, 0, 5)]);
+        assert_eq!(dest_overflow.overflowing().count(), 1);
+        assert_eq!(dest_overflow.map(2), 2);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let map = Map::new(vec![MapRange::new(0, 10, 5), MapRange::new(0, 20, 5)]);
+        assert_eq!(map.gaps(), vec![15..20]);
+        assert!(map.overlaps().is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use day_06::count_winning_hold_times;
 use nom::character::complete;
 use nom::{
     bytes::complete::{tag, take_until},
@@ -18,10 +19,12 @@ fn main() {
     let (_, (times, distance)) = parse_input(&data).unwrap();
 
     // zip times and distance together together as tuples
-    let times_distance: Vec<(u32, u32)> = times.into_iter().zip(distance.into_iter()).collect();
+    let times_distance: Vec<(u32, u32)> = times.into_iter().zip(distance).collect();
 
-    let sol = get_solution(times_distance);
-    println!("Result is {}", sol);
+    match get_solution(times_distance) {
+        Some(sol) => println!("Result is {}", sol),
+        None => println!("the product of the winning counts overflows a u128"),
+    }
 }
 
 fn parse_line(input: &str) -> IResult<&str, Vec<u32>> {
@@ -42,31 +45,33 @@ fn parse_input(input: &str) -> IResult<&str, (Vec<u32>, Vec<u32>)> {
     Ok((input, (times, distance)))
 }
 
-fn get_record_range_length(total_time: &u32, record: &u32) -> u32 {
-    // get the roots of the equation
-    // h = hold time = velocity
-    // T = total time
-    // R = record
-    // h(T-h) = R ->
-    // h^2 - Th + R = 0 ->
-    // a = 1, b = -T, c = R
-    // h = (-b +- sqrt(b^2 - 4ac)) / 2a
-    // since we know h, T, R are positive
-    // we can use the quadratic formula
-    let low_boundary =
-        (*total_time as f32 - ((total_time.pow(2) - (4 * record)) as f32).sqrt()) / (2) as f32;
-    let high_boundary =
-        (*total_time as f32 + ((total_time.pow(2) - (4 * record)) as f32).sqrt()) / (2) as f32;
-
-    // for values between these ranges, the distance travelled is greater
-    // than the record
-    let low_boundary = low_boundary.ceil() as u32;
-    let high_boundary = high_boundary.ceil() as u32;
-    (low_boundary..high_boundary).count() as u32
+/// The product of the winning counts, `None` if it overflows.
+fn get_solution(times_distance: Vec<(u32, u32)>) -> Option<u128> {
+    times_distance
+        .iter()
+        .try_fold(1u128, |prod, (time, distance)| {
+            prod.checked_mul(count_winning_hold_times(
+                &(*time as u128),
+                &(*distance as u128),
+            ))
+        })
 }
 
-fn get_solution(times_distance: Vec<(u32, u32)>) -> u32 {
-    times_distance.iter().fold(1, |prod, (time, distance)| {
-        prod * get_record_range_length(time, distance)
-    })
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = "Time:      7  15   30\nDistance:  9  40  200";
+        let (_, (times, distance)) = parse_input(input).unwrap();
+        let times_distance = times.into_iter().zip(distance).collect();
+        assert_eq!(get_solution(times_distance), Some(288));
+    }
+
+    #[test]
+    fn test_overflow() {
+        // each race wins u32::MAX - 1 ways, five of them don't fit in a u128
+        assert_eq!(get_solution(vec![(u32::MAX, 0); 5]), None);
+    }
 }
@@ -1,9 +1,11 @@
+use day_06::{count_winning_hold_times, winning_hold_times};
 use nom::{
     bytes::complete::{tag, take_until},
     character::complete::{digit1, multispace1},
     multi::separated_list1,
     IResult,
 };
+use num_bigint::BigUint;
 use std::env;
 use std::fs;
 
@@ -16,22 +18,28 @@ fn main() {
 
     let (_, (time, distance)) = parse_input(&data).unwrap();
 
-    // zip times and distance together together as tuples
+    // the kerned numbers can get long, so solve over BigUint
+    if let Some((low, high)) = winning_hold_times(&time, &distance) {
+        println!("Winning hold times {low}..={high}");
+    }
 
     let sol = get_solution(vec![(time, distance)]);
     println!("Result is {}", sol);
 }
 
-fn parse_line(input: &str) -> IResult<&str, u64> {
+fn parse_line(input: &str) -> IResult<&str, BigUint> {
     let (input, _) = take_until(":")(input)?;
     let (input, _) = tag(":")(input)?;
     let (input, _) = multispace1(input)?;
     let (_, data) = separated_list1(multispace1, digit1)(input)?;
-    let full_number = data.join("").parse::<u64>().expect("not a valid number");
+    let full_number = data
+        .join("")
+        .parse::<BigUint>()
+        .expect("not a valid number");
     Ok((input, full_number))
 }
 
-fn parse_input(input: &str) -> IResult<&str, (u64, u64)> {
+fn parse_input(input: &str) -> IResult<&str, (BigUint, BigUint)> {
     let mut lines = input.lines();
     let (_, time) = parse_line(lines.next().unwrap())?;
     let (_, distance) = parse_line(lines.next().unwrap())?;
@@ -40,31 +48,10 @@ fn parse_input(input: &str) -> IResult<&str, (u64, u64)> {
     Ok((input, (time, distance)))
 }
 
-fn get_record_range_length(total_time: &u64, record: &u64) -> u64 {
-    // get the roots of the equation
-    // h = hold time = velocity
-    // T = total time
-    // R = record
-    // h(T-h) = R ->
-    // h^2 - Th + R = 0 ->
-    // a = 1, b = -T, c = R
-    // h = (-b +- sqrt(b^2 - 4ac)) / 2a
-    // since we know h, T, R are positive
-    // we can use the quadratic formula
-    let low_boundary =
-        (*total_time as f32 - ((total_time.pow(2) - (4 * record)) as f32).sqrt()) / (2) as f32;
-    let high_boundary =
-        (*total_time as f32 + ((total_time.pow(2) - (4 * record)) as f32).sqrt()) / (2) as f32;
-
-    // for values between these ranges, the distance travelled is greater
-    // than the record
-    let low_boundary = low_boundary.ceil() as u64;
-    let high_boundary = high_boundary.ceil() as u64;
-    (low_boundary..high_boundary).count() as u64
-}
-
-fn get_solution(times_distance: Vec<(u64, u64)>) -> u64 {
-    times_distance.iter().fold(1, |prod, (time, distance)| {
-        prod * get_record_range_length(time, distance)
-    })
+fn get_solution(times_distance: Vec<(BigUint, BigUint)>) -> BigUint {
+    times_distance
+        .iter()
+        .fold(BigUint::from(1u32), |prod, (time, distance)| {
+            prod * count_winning_hold_times(time, distance)
+        })
 }
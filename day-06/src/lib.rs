@@ -0,0 +1,191 @@
+use num_integer::{Integer, Roots};
+use num_traits::CheckedMul;
+
+// get the roots of the equation
+// h = hold time = velocity
+// T = total time
+// R = record
+// h(T-h) > R ->
+// h^2 - Th + R < 0 ->
+// h = (T +- sqrt(T^2 - 4R)) / 2
+// instead of floats we take the integer square root and then nudge the
+// boundary by a step so that ties with the record are handled exactly.
+// when T^2 doesn't fit in the type the boundary is binary searched instead.
+// works for any unsigned integer type, including BigUint.
+
+// whether holding for `hold_time` (at most `total_time`) beats `record`. A
+// distance too big for the type beats any record the type can hold.
+fn beats<T: Integer + CheckedMul + Clone>(hold_time: &T, total_time: &T, record: &T) -> bool {
+    match hold_time.checked_mul(&(total_time.clone() - hold_time.clone())) {
+        Some(distance) => distance > *record,
+        None => true,
+    }
+}
+
+// the shortest winning hold time, given that holding for `half` wins.
+// Distance only grows up to half way, so it's the first that beats the record.
+fn search_low<T: Integer + CheckedMul + Clone>(half: &T, total_time: &T, record: &T) -> T {
+    let (mut low, mut high) = (T::zero(), half.clone());
+    while low < high {
+        let middle = (low.clone() + high.clone()) / (T::one() + T::one());
+        if beats(&middle, total_time, record) {
+            high = middle;
+        } else {
+            low = middle + T::one();
+        }
+    }
+    low
+}
+
+/// The hold times that beat `record`, as an inclusive `(low, high)` interval.
+/// `None` if no hold time beats it.
+pub fn winning_hold_times<T>(total_time: &T, record: &T) -> Option<(T, T)>
+where
+    T: Integer + Roots + CheckedMul + Clone,
+{
+    let two = T::one() + T::one();
+    let four = two.clone() * two.clone();
+    let half = total_time.clone() / two.clone();
+    if !beats(&half, total_time, record) {
+        // even holding for half the race only ties the record at best
+        return None;
+    }
+    let squared = total_time.checked_mul(total_time);
+    let four_record = four.checked_mul(record);
+    let mut low = match (squared, four_record) {
+        // T^2 > 4R since half the race beats the record
+        (Some(squared), Some(four_record)) => {
+            let root = (squared - four_record).sqrt();
+            if root >= *total_time {
+                T::zero()
+            } else {
+                ((total_time.clone() - root) / two).min(half.clone())
+            }
+        }
+        _ => search_low(&half, total_time, record),
+    };
+    // both stop by half, which wins
+    while !beats(&low, total_time, record) {
+        low = low + T::one();
+    }
+    while !low.is_zero() && beats(&(low.clone() - T::one()), total_time, record) {
+        low = low - T::one();
+    }
+    // the distance curve is symmetric around T/2
+    let high = total_time.clone() - low.clone();
+    Some((low, high))
+}
+
+/// How many hold times beat `record`.
+pub fn count_winning_hold_times<T>(total_time: &T, record: &T) -> T
+where
+    T: Integer + Roots + CheckedMul + Clone,
+{
+    match winning_hold_times(total_time, record) {
+        Some((low, high)) => high - low + T::one(),
+        None => T::zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+
+    fn brute_force(total_time: u64, record: u64) -> Option<(u64, u64)> {
+        let winning = (0..=total_time)
+            .filter(|hold_time| hold_time * (total_time - hold_time) > record)
+            .collect::<Vec<u64>>();
+        Some((*winning.first()?, *winning.last()?))
+    }
+
+    #[test]
+    fn test_example_races() {
+        assert_eq!(winning_hold_times(&7u128, &9), Some((2, 5)));
+        assert_eq!(winning_hold_times(&15u128, &40), Some((4, 11)));
+        // 10 * 20 ties the record, so only 11..=19 win
+        assert_eq!(winning_hold_times(&30u128, &200), Some((11, 19)));
+        assert_eq!(count_winning_hold_times(&71530u128, &940200), 71503);
+    }
+
+    #[test]
+    fn test_no_winners() {
+        assert_eq!(winning_hold_times(&0u128, &0), None);
+        // holding for 5 of 10 ties 25 exactly
+        assert_eq!(winning_hold_times(&10u128, &25), None);
+        assert_eq!(winning_hold_times(&10u128, &100), None);
+        assert_eq!(count_winning_hold_times(&10u128, &25), 0);
+        // the best holds only tie: 1 * 2 and 2 * 1, 2 * 3 and 3 * 2
+        assert_eq!(winning_hold_times(&3u128, &2), None);
+        assert_eq!(winning_hold_times(&5u128, &6), None);
+        assert_eq!(winning_hold_times(&5u128, &5), Some((2, 3)));
+    }
+
+    #[test]
+    fn test_past_u64() {
+        // T^2 doesn't fit in u128, so this searches for the boundary
+        let total_time: u128 = 1 << 70;
+        assert_eq!(
+            winning_hold_times(&total_time, &0),
+            Some((1, total_time - 1))
+        );
+        for record in [1 << 100, u128::MAX - 1, u128::MAX] {
+            let big = winning_hold_times(&BigUint::from(total_time), &BigUint::from(record))
+                .map(|(low, high)| (low.try_into().unwrap(), high.try_into().unwrap()));
+            assert_eq!(winning_hold_times(&total_time, &record), big);
+        }
+    }
+
+    #[test]
+    fn test_big_race() {
+        // far past u128: T = 10^40, R = T^2 / 4 - 1 leaves only the midpoint
+        let total_time = BigUint::from(10u32).pow(40);
+        let record = total_time.clone() * total_time.clone() / 4u32 - 1u32;
+        let half = total_time.clone() / 2u32;
+        assert_eq!(
+            winning_hold_times(&total_time, &record),
+            Some((half.clone(), half))
+        );
+        assert_eq!(
+            count_winning_hold_times(&total_time, &(record + 1u32)),
+            BigUint::from(0u32)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_brute_force(total_time in 0u64..400, record in 0u64..40_000) {
+            let expected = brute_force(total_time, record);
+            let exact = winning_hold_times(&(total_time as u128), &(record as u128))
+                .map(|(low, high)| (low as u64, high as u64));
+            prop_assert_eq!(exact, expected);
+            let big = winning_hold_times(&BigUint::from(total_time), &BigUint::from(record));
+            prop_assert_eq!(big, expected.map(|(low, high)| (BigUint::from(low), BigUint::from(high))));
+        }
+
+        #[test]
+        fn test_ties_match_brute_force(
+            (total_time, hold_time) in (0u64..400).prop_flat_map(|total_time| (Just(total_time), 0..=total_time))
+        ) {
+            // the record is exactly what some hold time gets
+            let record = hold_time * (total_time - hold_time);
+            let exact = winning_hold_times(&(total_time as u128), &(record as u128))
+                .map(|(low, high)| (low as u64, high as u64));
+            prop_assert_eq!(exact, brute_force(total_time, record));
+        }
+
+        #[test]
+        fn test_boundaries_are_tight(total_time in 0u128..(1 << 60), record in 0u128..(1 << 100)) {
+            let distance = |hold_time: u128| hold_time * (total_time - hold_time);
+            match winning_hold_times(&total_time, &record) {
+                Some((low, high)) => {
+                    prop_assert!(distance(low) > record && distance(high) > record);
+                    prop_assert!(low == 0 || distance(low - 1) <= record);
+                    prop_assert!(high == total_time || distance(high + 1) <= record);
+                }
+                None => prop_assert!(distance(total_time / 2) <= record),
+            }
+        }
+    }
+}
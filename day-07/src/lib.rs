@@ -0,0 +1,665 @@
+use nom::bytes::complete::{tag, take_till1};
+use nom::character::complete;
+use nom::combinator::map_res;
+use nom::multi::separated_list1;
+use nom::sequence::separated_pair;
+use nom::IResult;
+use std::cmp::{Ord, Ordering, PartialEq, PartialOrd};
+
+/// Everything that differs between Camel Cards variants.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleSet {
+    // card symbols from weakest to strongest
+    pub order: Vec<char>,
+    // symbols that act as whatever card makes the best hand
+    pub wild: Vec<char>,
+    // when breaking ties, wildcards count as weaker than every other card
+    pub wild_loses_ties: bool,
+    // None allows hands of any length
+    pub hand_size: Option<usize>,
+}
+
+impl RuleSet {
+    /// Part 1: J is a Jack.
+    pub fn standard() -> RuleSet {
+        RuleSet {
+            order: "23456789TJQKA".chars().collect(),
+            wild: Vec::new(),
+            wild_loses_ties: false,
+            hand_size: Some(5),
+        }
+    }
+
+    /// Part 2: J is a Joker.
+    pub fn jokers() -> RuleSet {
+        RuleSet {
+            order: "23456789TJQKA".chars().collect(),
+            wild: vec!['J'],
+            wild_loses_ties: true,
+            hand_size: Some(5),
+        }
+    }
+
+    pub fn preset(name: &str) -> Option<RuleSet> {
+        match name {
+            "standard" => Some(RuleSet::standard()),
+            "jokers" => Some(RuleSet::jokers()),
+            _ => None,
+        }
+    }
+
+    /// Reads `key = value` lines. Keys that are left out keep the value from
+    /// `preset`, which defaults to `standard`:
+    ///
+    /// ```text
+    /// preset = jokers
+    /// order = 23456789TJQKA
+    /// wild = J
+    /// wild_loses_ties = true
+    /// hand_size = 5
+    /// ```
+    ///
+    /// `hand_size = any` accepts hands of every length.
+    pub fn from_config(config: &str) -> Result<RuleSet, String> {
+        let mut entries = Vec::new();
+        for line in config.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("expected key = value, got {line:?}"))?;
+            entries.push((key.trim(), value.trim()));
+        }
+
+        let mut rules = match entries.iter().find(|(key, _)| *key == "preset") {
+            Some((_, name)) => {
+                RuleSet::preset(name).ok_or_else(|| format!("unknown preset {name:?}"))?
+            }
+            None => RuleSet::standard(),
+        };
+        for (key, value) in entries {
+            match key {
+                "preset" => {}
+                "order" => rules.order = value.chars().collect(),
+                "wild" => {
+                    rules.wild = Vec::new();
+                    for entry in value.split(',').map(str::trim) {
+                        let mut chars = entry.chars();
+                        match (chars.next(), chars.next()) {
+                            (None, _) => {}
+                            (Some(symbol), None) => rules.wild.push(symbol),
+                            _ => return Err(format!("wildcard {entry:?} should be one card")),
+                        }
+                    }
+                }
+                "wild_loses_ties" => {
+                    rules.wild_loses_ties = value
+                        .parse()
+                        .map_err(|_| "wild_loses_ties should be true or false".to_string())?
+                }
+                "hand_size" if value == "any" => rules.hand_size = None,
+                "hand_size" => {
+                    rules.hand_size = Some(value.parse().map_err(|_| {
+                        format!("hand_size should be a number or any, got {value:?}")
+                    })?)
+                }
+                _ => return Err(format!("unknown rule {key:?}")),
+            }
+        }
+        rules.check()?;
+        Ok(rules)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        for (i, symbol) in self.order.iter().enumerate() {
+            if self.order[..i].contains(symbol) {
+                return Err(format!("{symbol} appears twice in the card order"));
+            }
+        }
+        if let Some(symbol) = self.wild.iter().find(|c| !self.order.contains(c)) {
+            return Err(format!("wildcard {symbol} is not in the card order"));
+        }
+        if self.hand_size == Some(0) {
+            return Err("hand_size must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn is_wild(&self, symbol: char) -> bool {
+        self.wild.contains(&symbol)
+    }
+
+    /// Tie-break strength of a card, higher beating lower. Cards count up
+    /// from 1 in card order, unless wildcards lose ties: then the wildcards
+    /// take 0, 1, ... and the other cards follow on from there. Every symbol
+    /// gets its own strength, so hands only tie when they are the same cards.
+    pub fn strength(&self, symbol: char) -> Option<usize> {
+        let position = self.order.iter().position(|c| *c == symbol)?;
+        if self.wild_loses_ties && !self.wild.is_empty() {
+            // wildcards come first, in card order, then everything else
+            let wild_before = self.order[..position]
+                .iter()
+                .filter(|c| self.is_wild(**c))
+                .count();
+            if self.is_wild(symbol) {
+                Some(wild_before)
+            } else {
+                Some(self.wild.len() + position - wild_before)
+            }
+        } else {
+            Some(position + 1)
+        }
+    }
+}
+
+/// The sizes of the groups of equal cards, largest first, e.g. `[3, 2]` for
+/// a full house. Comparing these lexicographically gives the usual ranking
+/// (five of a kind > four of a kind > full house > ...) for five cards, and
+/// extends it to any hand size: `[3, 3, 1]` beats `[3, 2, 2]`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone)]
+pub struct HandType {
+    pub counts: Vec<usize>,
+}
+
+fn number_name(n: usize) -> String {
+    let names = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+    match names.get(n) {
+        Some(name) => name.to_string(),
+        None => n.to_string(),
+    }
+}
+
+impl HandType {
+    pub fn of(counts: &[usize]) -> HandType {
+        HandType {
+            counts: counts.to_vec(),
+        }
+    }
+
+    /// "full house", "two pair", "two triples", "four of a kind and one pair", ...
+    pub fn name(&self) -> String {
+        let groups = self
+            .counts
+            .iter()
+            .copied()
+            .filter(|count| *count >= 2)
+            .collect::<Vec<usize>>();
+        if groups.is_empty() {
+            return "high card".to_string();
+        }
+        if groups == [3, 2] {
+            return "full house".to_string();
+        }
+        let mut parts = Vec::new();
+        // groups are sorted, so equal sizes sit next to each other
+        let mut i = 0;
+        while i < groups.len() {
+            let size = groups[i];
+            let same = groups[i..].iter().take_while(|g| **g == size).count();
+            parts.push(match (size, same) {
+                (2, 1) => "one pair".to_string(),
+                (2, n) => format!("{} pair", number_name(n)),
+                (3, n) if n > 1 => format!("{} triples", number_name(n)),
+                (size, 1) => format!("{} of a kind", number_name(size)),
+                (size, n) => format!("{} {} of a kinds", number_name(n), number_name(size)),
+            });
+            i += same;
+        }
+        parts.join(" and ")
+    }
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct Hand {
+    pub symbols: String,
+    // tie-break strength of each card, in the order they were dealt
+    pub cards: Vec<usize>,
+    pub hand_type: HandType,
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.hand_type.cmp(&other.hand_type) {
+            Ordering::Equal => {
+                // same hand type, compare cards
+                self.cards.cmp(&other.cards)
+            }
+            ord => ord,
+        }
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hand {
+    pub fn new(symbols: &str, rules: &RuleSet) -> Result<Hand, String> {
+        if let Some(hand_size) = rules.hand_size {
+            if symbols.chars().count() != hand_size {
+                return Err(format!("{symbols:?} does not have {hand_size} cards"));
+            }
+        }
+        let cards = symbols
+            .chars()
+            .map(|c| {
+                rules
+                    .strength(c)
+                    .ok_or_else(|| format!("{c} is not a valid card"))
+            })
+            .collect::<Result<Vec<usize>, String>>()?;
+        let hand_type = Hand::get_hand_type(symbols, rules);
+        Ok(Hand {
+            symbols: symbols.to_string(),
+            cards,
+            hand_type,
+        })
+    }
+
+    pub fn get_hand_type(symbols: &str, rules: &RuleSet) -> HandType {
+        HandType {
+            counts: Hand::count_cards(symbols, rules),
+        }
+    }
+
+    /// Counts of each distinct card, largest first, with the wildcards
+    /// added to the largest count. That is always the best use of them since
+    /// hand types compare on the largest group first.
+    pub fn count_cards(symbols: &str, rules: &RuleSet) -> Vec<usize> {
+        let mut card_counts = vec![0; rules.order.len()];
+        let mut wild_count = 0;
+        for symbol in symbols.chars() {
+            if rules.is_wild(symbol) {
+                wild_count += 1;
+            } else if let Some(position) = rules.order.iter().position(|c| *c == symbol) {
+                card_counts[position] += 1;
+            }
+        }
+        card_counts.retain(|count| *count > 0);
+        card_counts.sort();
+        card_counts.reverse();
+        // now we need to handle the wildcards
+        match card_counts.first_mut() {
+            Some(count) => *count += wild_count,
+            None => card_counts.push(wild_count),
+        }
+        card_counts
+    }
+}
+
+pub type Bet = (Hand, u32);
+
+fn parse_bet<'a>(input: &'a str, rules: &RuleSet) -> IResult<&'a str, Bet> {
+    separated_pair(
+        map_res(take_till1(|c| c == ' '), |symbols| {
+            Hand::new(symbols, rules)
+        }),
+        tag(" "),
+        complete::u32,
+    )(input)
+}
+
+pub fn parse_input<'a>(input: &'a str, rules: &RuleSet) -> IResult<&'a str, Vec<Bet>> {
+    separated_list1(complete::line_ending, |line| parse_bet(line, rules))(input)
+}
+
+/// Sorts the bets from weakest to strongest and sums bid * rank.
+pub fn total_winnings(bets: &mut [Bet]) -> u32 {
+    bets.sort_by(|(hand1, _), (hand2, _)| hand1.cmp(hand2));
+
+    bets.iter().enumerate().fold(0, |mut acc, (i, (_, bid))| {
+        acc += bid * (i + 1) as u32;
+        acc
+    })
+}
+
+/// Why `hand` ranks above `weaker`, the hand just below it.
+fn beats(hand: &Hand, weaker: &Hand) -> String {
+    if hand.hand_type != weaker.hand_type {
+        return format!("beats {} on hand type", weaker.symbols);
+    }
+    match hand
+        .cards
+        .iter()
+        .zip(weaker.cards.iter())
+        .position(|(a, b)| a != b)
+    {
+        Some(i) => format!(
+            "beats {} at card {} ({} over {})",
+            weaker.symbols,
+            i + 1,
+            hand.symbols.chars().nth(i).unwrap(),
+            weaker.symbols.chars().nth(i).unwrap()
+        ),
+        None if hand.cards.len() > weaker.cards.len() => {
+            format!("beats {} on hand length", weaker.symbols)
+        }
+        None => format!("ties with {}", weaker.symbols),
+    }
+}
+
+impl Hand {
+    /// e.g. `KTJJT four of a kind [4, 1] (2 wild)`
+    pub fn explain(&self, rules: &RuleSet) -> String {
+        let wild = self.symbols.chars().filter(|c| rules.is_wild(*c)).count();
+        let mut explanation = format!(
+            "{} {} {:?}",
+            self.symbols,
+            self.hand_type.name(),
+            self.hand_type.counts
+        );
+        if wild > 0 {
+            explanation += &format!(" ({wild} wild)");
+        }
+        explanation
+    }
+}
+
+/// One line per bet, weakest first, saying what the hand is, why it sits
+/// above the previous one and what it wins. Sorts `bets`.
+pub fn explain_ranking(bets: &mut [Bet], rules: &RuleSet) -> Vec<String> {
+    bets.sort_by(|(hand1, _), (hand2, _)| hand1.cmp(hand2));
+    bets.iter()
+        .enumerate()
+        .map(|(i, (hand, bid))| {
+            let rank = i + 1;
+            let mut line = format!("{rank}: {}", hand.explain(rules));
+            if i > 0 {
+                line += &format!(", {}", beats(hand, &bets[i - 1].0));
+            }
+            line + &format!(", wins {bid} * {rank} = {}", *bid as usize * rank)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_test_input() -> String {
+        "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483"
+            .to_string()
+    }
+
+    // classify by number of distinct cards and the biggest group, without sorting counts
+    fn reference_hand_type(symbols: &[char]) -> &'static str {
+        let distinct = symbols
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| !symbols[..*i].contains(c))
+            .count();
+        let largest = symbols
+            .iter()
+            .map(|c| symbols.iter().filter(|other| *other == c).count())
+            .max()
+            .unwrap();
+        match (distinct, largest) {
+            (1, _) => "five of a kind",
+            (2, 4) => "four of a kind",
+            (2, _) => "full house",
+            (3, 3) => "three of a kind",
+            (3, _) => "two pair",
+            (4, _) => "one pair",
+            _ => "high card",
+        }
+    }
+
+    fn reference_rank(name: &str) -> usize {
+        [
+            "high card",
+            "one pair",
+            "two pair",
+            "three of a kind",
+            "full house",
+            "four of a kind",
+            "five of a kind",
+        ]
+        .iter()
+        .position(|n| *n == name)
+        .unwrap()
+    }
+
+    // try every replacement for every wildcard and keep the best
+    fn reference_wild_hand_type(symbols: &[char], rules: &RuleSet) -> &'static str {
+        match symbols.iter().position(|c| rules.is_wild(*c)) {
+            None => reference_hand_type(symbols),
+            Some(i) => rules
+                .order
+                .iter()
+                .filter(|c| !rules.is_wild(**c))
+                .map(|c| {
+                    let mut replaced = symbols.to_vec();
+                    replaced[i] = *c;
+                    reference_wild_hand_type(&replaced, rules)
+                })
+                .max_by_key(|name| reference_rank(name))
+                .unwrap(),
+        }
+    }
+
+    fn all_hands(alphabet: &[char], hand_size: usize) -> Vec<Vec<char>> {
+        (0..hand_size).fold(vec![Vec::new()], |hands, _| {
+            hands
+                .iter()
+                .flat_map(|hand| {
+                    alphabet.iter().map(move |c| {
+                        let mut hand = hand.clone();
+                        hand.push(*c);
+                        hand
+                    })
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_example_standard() {
+        let input = make_test_input();
+        let (_, mut bets) = parse_input(&input, &RuleSet::standard()).unwrap();
+        assert_eq!(total_winnings(&mut bets), 6440);
+    }
+
+    #[test]
+    fn test_example_jokers() {
+        let input = make_test_input();
+        let (_, mut bets) = parse_input(&input, &RuleSet::jokers()).unwrap();
+        assert_eq!(total_winnings(&mut bets), 5905);
+    }
+
+    #[test]
+    fn test_classify_every_standard_hand() {
+        let rules = RuleSet::standard();
+        for hand in all_hands(&rules.order, 5) {
+            let symbols = hand.iter().collect::<String>();
+            let hand_type = Hand::get_hand_type(&symbols, &rules);
+            assert_eq!(hand_type.name(), reference_hand_type(&hand), "{symbols}");
+        }
+    }
+
+    #[test]
+    fn test_classify_every_wild_hand() {
+        // every hand type can be made from five plain cards plus the wildcard
+        let rules = RuleSet::from_config("preset = jokers\norder = 2345J6").unwrap();
+        for hand in all_hands(&rules.order, 5) {
+            let symbols = hand.iter().collect::<String>();
+            let hand_type = Hand::get_hand_type(&symbols, &rules);
+            assert_eq!(
+                hand_type.name(),
+                reference_wild_hand_type(&hand, &rules),
+                "{symbols}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_wild_tie_breaks() {
+        let jokers = RuleSet::jokers();
+        assert!(Hand::new("JKKK2", &jokers).unwrap() < Hand::new("TTTT2", &jokers).unwrap());
+        let mut rules = RuleSet::jokers();
+        rules.wild_loses_ties = false;
+        assert!(Hand::new("JKKK2", &rules).unwrap() > Hand::new("TTTT2", &rules).unwrap());
+    }
+
+    #[test]
+    fn test_several_wildcards() {
+        let rules = RuleSet::from_config("preset = jokers\nwild = J,T").unwrap();
+        // both wildcards are weaker than a 2, and T is weaker than J
+        assert!(rules.strength('T') < rules.strength('J'));
+        assert!(rules.strength('J') < rules.strength('2'));
+        assert!(rules.strength('9') < rules.strength('Q'));
+        let tens = Hand::new("T2345", &rules).unwrap();
+        let jacks = Hand::new("J2345", &rules).unwrap();
+        assert_ne!(tens, jacks);
+        assert_eq!(tens.cmp(&jacks), Ordering::Less);
+        assert_eq!(
+            tens.cmp(&Hand::new("T2345", &rules).unwrap()),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_long_card_order() {
+        // more symbols than fit in a byte still get a strength each
+        let rules = RuleSet {
+            order: (0..300)
+                .map(|i| char::from_u32(0x100 + i).unwrap())
+                .collect(),
+            wild: Vec::new(),
+            wild_loses_ties: false,
+            hand_size: Some(1),
+        };
+        let weak = Hand::new(&rules.order[0].to_string(), &rules).unwrap();
+        let strong = Hand::new(&rules.order[256].to_string(), &rules).unwrap();
+        assert_ne!(weak, strong);
+        assert_eq!(weak.cmp(&strong), Ordering::Less);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let rules = RuleSet::from_config(
+            "# jokers and tens are wild, three card hands
+preset = jokers
+wild = J,T
+hand_size = 3",
+        )
+        .unwrap();
+        assert_eq!(rules.wild, vec!['J', 'T']);
+        assert!(rules.wild_loses_ties);
+        assert_eq!(rules.hand_size, Some(3));
+        assert_eq!(
+            Hand::new("TJ2", &rules).unwrap().hand_type,
+            HandType::of(&[3])
+        );
+        assert!(Hand::new("TJ22", &rules).is_err());
+
+        let spaced = RuleSet::from_config("wild = J, T,").unwrap();
+        assert_eq!(spaced.wild, vec!['J', 'T']);
+        assert!(RuleSet::from_config("wild = JT").is_err());
+        assert!(RuleSet::from_config("wild = X").is_err());
+        assert!(RuleSet::from_config("order = 22").is_err());
+        assert!(RuleSet::from_config("colour = red").is_err());
+        assert!(RuleSet::from_config("preset = poker").is_err());
+    }
+
+    #[test]
+    fn parse_hand_test() {
+        let hand = Hand::new("32T3K", &RuleSet::standard()).unwrap();
+        assert_eq!(hand.hand_type, HandType::of(&[2, 1, 1, 1]));
+        assert_eq!(hand.cards, vec![2, 1, 9, 2, 12]);
+    }
+
+    #[test]
+    fn parse_bet_test() {
+        let input = "32T3K 765";
+        let (out_str, (hand, bid)) = parse_bet(input, &RuleSet::standard()).unwrap();
+        assert_eq!(hand.symbols, "32T3K");
+        assert_eq!(hand.hand_type, HandType::of(&[2, 1, 1, 1]));
+        assert_eq!(bid, 765);
+        assert_eq!(out_str, "");
+        assert!(parse_bet("32X3K 765", &RuleSet::standard()).is_err());
+    }
+
+    #[test]
+    fn test_classification_order_matches_puzzle() {
+        let rules = RuleSet::standard();
+        let hands = [
+            "23456", "22345", "22334", "22234", "22233", "22223", "22222",
+        ];
+        for pair in hands.windows(2) {
+            let weaker = Hand::get_hand_type(pair[0], &rules);
+            let stronger = Hand::get_hand_type(pair[1], &rules);
+            assert!(weaker < stronger, "{} < {}", pair[0], pair[1]);
+            assert_eq!(
+                reference_rank(&weaker.name()) + 1,
+                reference_rank(&stronger.name())
+            );
+        }
+    }
+
+    #[test]
+    fn test_seven_card_hands() {
+        let rules = RuleSet::from_config("hand_size = 7").unwrap();
+        let two_triples = Hand::new("2223334", &rules).unwrap();
+        let triple_two_pair = Hand::new("AAAKKQQ", &rules).unwrap();
+        assert_eq!(two_triples.hand_type, HandType::of(&[3, 3, 1]));
+        assert_eq!(two_triples.hand_type.name(), "two triples");
+        assert_eq!(
+            triple_two_pair.hand_type.name(),
+            "three of a kind and two pair"
+        );
+        assert!(two_triples > triple_two_pair);
+        assert_eq!(
+            Hand::new("2222333", &rules).unwrap().hand_type.name(),
+            "four of a kind and three of a kind"
+        );
+        assert_eq!(
+            Hand::new("2233445", &rules).unwrap().hand_type.name(),
+            "three pair"
+        );
+        assert!(Hand::new("22233", &rules).is_err());
+    }
+
+    #[test]
+    fn test_any_hand_size() {
+        let rules = RuleSet::from_config("preset = jokers\nhand_size = any").unwrap();
+        let (_, mut bets) = parse_input("22 1\nJJJ 10\n2 100\nAK 1000", &rules).unwrap();
+        let ranking = explain_ranking(&mut bets, &rules);
+        let order = bets
+            .iter()
+            .map(|(hand, _)| hand.symbols.as_str())
+            .collect::<Vec<_>>();
+        // a hand that is a prefix of another hand's group sizes is weaker
+        assert_eq!(order, vec!["2", "AK", "22", "JJJ"]);
+        assert_eq!(
+            ranking[3],
+            "4: JJJ three of a kind [3] (3 wild), beats 22 on hand type, wins 10 * 4 = 40"
+        );
+    }
+
+    #[test]
+    fn test_explain_ranking() {
+        let rules = RuleSet::jokers();
+        let (_, mut bets) = parse_input(&make_test_input(), &rules).unwrap();
+        let ranking = explain_ranking(&mut bets, &rules);
+        assert_eq!(
+            ranking,
+            vec![
+                "1: 32T3K one pair [2, 1, 1, 1], wins 765 * 1 = 765",
+                "2: KK677 two pair [2, 2, 1], beats 32T3K on hand type, wins 28 * 2 = 56",
+                "3: T55J5 four of a kind [4, 1] (1 wild), beats KK677 on hand type, wins 684 * 3 = 2052",
+                "4: QQQJA four of a kind [4, 1] (1 wild), beats T55J5 at card 1 (Q over T), wins 483 * 4 = 1932",
+                "5: KTJJT four of a kind [4, 1] (2 wild), beats QQQJA at card 1 (K over Q), wins 220 * 5 = 1100",
+            ]
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use day_08::{
+    node_name, parse_input, solve_ghosts, start_nodes, to_dot, unreachable_nodes, GhostCycle,
+};
+use std::env;
+use std::fs;
+
+// usage: analyse <filepath> <unreachable|starts|dot>
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        panic!("usage: analyse <filepath> <unreachable|starts|dot>")
+    }
+    let input = fs::read_to_string(&args[1]).expect("Error reading input file");
+    let (_, (directions, map)) = parse_input(&input).unwrap();
+
+    match args[2].as_str() {
+        "unreachable" => {
+            let unreachable = unreachable_nodes(&map);
+            println!("{} of {} nodes unreachable", unreachable.len(), map.len());
+            for node in unreachable {
+                println!("{}", node_name(&node));
+            }
+        }
+        "starts" => {
+            let mut cycles = Vec::new();
+            for start in start_nodes(&map) {
+                let cycle = GhostCycle::detect(&start, &directions, &map).unwrap();
+                let first_hit = match cycle.first_hit() {
+                    Some(steps) => format!("reaches ..Z after {steps} steps"),
+                    None => "never reaches ..Z".to_string(),
+                };
+                println!(
+                    "{}: {first_hit}, cycle of {} steps after {}, ..Z at {:?} then {:?}",
+                    node_name(&start),
+                    cycle.period,
+                    cycle.prefix,
+                    cycle.prefix_hits,
+                    cycle.cycle_hits
+                );
+                cycles.push(cycle);
+            }
+            match solve_ghosts(&cycles) {
+                Some((steps, method)) => {
+                    println!("all on ..Z after {steps} steps, solved with {method:?}")
+                }
+                None => println!("the ghosts never all reach a ..Z node together"),
+            }
+        }
+        "dot" => print!("{}", to_dot(&map)),
+        command => panic!("unknown command {command}"),
+    }
+}
@@ -1,99 +1,25 @@
-use nom::bytes::complete::tag;
-use nom::character::complete::{alpha1, newline};
-use nom::multi::separated_list1;
-use nom::sequence::{delimited, separated_pair, tuple};
-use nom::IResult;
-use std::collections::HashMap;
+use day_08::{parse_input, search_length, Direction, Map};
 use std::env;
 use std::fs;
 
-#[derive(Debug, PartialEq)]
-enum Direction {
-    L = 0,
-    R = 1,
-}
-type Children = ([char; 3], [char; 3]);
-type Parent = [char; 3];
-type Map = HashMap<Parent, Children>;
-
 fn main() {
     let args: Vec<String> = env::args().collect();
     let input = fs::read_to_string(&args[1]).expect("Error reading input file");
     let (_, (directions, map)) = parse_input(&input).unwrap();
-    let solution = get_solution(directions, map);
-    println!("Solution: {}", solution);
-}
-
-fn get_solution(directions: Vec<Direction>, map: Map) -> usize {
-    let mut current_node = ['A'; 3];
-    let mut step_count = 0;
-    for direction in directions.iter().cycle() {
-        let children = map.get(&current_node).unwrap();
-        match direction {
-            Direction::L => current_node = children.0,
-            Direction::R => current_node = children.1,
-        }
-        step_count += 1;
-        if current_node == ['Z'; 3] {
-            break;
-        }
+    match get_solution(directions, map) {
+        Some(solution) => println!("Solution: {}", solution),
+        None => println!("ZZZ can't be reached from AAA"),
     }
-    step_count
 }
 
-fn parse_directions(input: &str) -> IResult<&str, Vec<Direction>> {
-    let (input, direction_str) = alpha1(input)?;
-    let directions = direction_str
-        .chars()
-        .map(|c| match c {
-            'L' => Direction::L,
-            'R' => Direction::R,
-            _ => panic!("Invalid direction"),
-        })
-        .collect();
-    Ok((input, directions))
-}
-
-fn parse_node_name(input: &str) -> IResult<&str, Parent> {
-    let mut parent = ['A'; 3];
-    let (input, parent_str) = alpha1(input)?;
-    for (i, c) in parent_str.chars().enumerate() {
-        parent[i] = c;
-    }
-
-    Ok((input, parent))
-}
-
-fn parse_children(input: &str) -> IResult<&str, Children> {
-    delimited(
-        tag("("),
-        separated_pair(parse_node_name, tag(", "), parse_node_name),
-        tag(")"),
-    )(input)
-}
-
-fn parse_node(input: &str) -> IResult<&str, (Parent, Children)> {
-    separated_pair(parse_node_name, tag(" = "), parse_children)(input)
-}
-
-fn parse_nodes(input: &str) -> IResult<&str, Vec<(Parent, Children)>> {
-    separated_list1(newline, parse_node)(input)
-}
-
-fn parse_input(input: &str) -> IResult<&str, (Vec<Direction>, Map)> {
-    let (input, directions) = parse_directions(input)?;
-    let (input, _) = tuple((newline, newline))(input)?;
-    let mut map = HashMap::new();
-    let (_, nodes) = parse_nodes(input)?;
-    for (parent, children) in nodes {
-        map.insert(parent, children);
-    }
-    Ok(("", (directions, map)))
+fn get_solution(directions: Vec<Direction>, map: Map) -> Option<usize> {
+    search_length(&['A'; 3], &directions, &map, |node| *node == ['Z'; 3]).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use day_08::{parse_children, parse_directions, parse_node, parse_node_name};
 
     #[test]
     fn test_parse_directions() {
@@ -152,6 +78,18 @@ BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
 
         let (_, (directions, map)) = parse_input(input).unwrap();
-        assert_eq!(get_solution(directions, map), 6);
+        assert_eq!(get_solution(directions, map), Some(6));
+    }
+
+    #[test]
+    fn test_unreachable_solution() {
+        let input = "L
+
+AAA = (BBB, ZZZ)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+
+        let (_, (directions, map)) = parse_input(input).unwrap();
+        assert_eq!(get_solution(directions, map), None);
     }
 }
@@ -0,0 +1,619 @@
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha1, alphanumeric1, newline};
+use nom::multi::separated_list1;
+use nom::sequence::{delimited, separated_pair, tuple};
+use nom::IResult;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Direction {
+    L = 0,
+    R = 1,
+}
+pub type Children = ([char; 3], [char; 3]);
+pub type Parent = [char; 3];
+pub type Map = HashMap<Parent, Children>;
+
+pub fn is_start(node: &Parent) -> bool {
+    node[2] == 'A'
+}
+
+pub fn is_end(node: &Parent) -> bool {
+    node[2] == 'Z'
+}
+
+fn step(node: &Parent, direction: &Direction, map: &Map) -> Result<Parent, String> {
+    let children = map
+        .get(node)
+        .ok_or_else(|| format!("node {} is not in the map", node_name(node)))?;
+    Ok(match direction {
+        Direction::L => children.0,
+        Direction::R => children.1,
+    })
+}
+
+pub fn node_name(node: &Parent) -> String {
+    node.iter().collect()
+}
+
+pub fn gcd(a: usize, b: usize) -> usize {
+    // make sure a is the larger number
+    if a < b {
+        return gcd(b, a);
+    }
+    if b == 0 {
+        return a;
+    }
+    gcd(b, a % b)
+}
+
+/// `None` if the result doesn't fit in a usize.
+pub fn lcm(a: usize, b: usize) -> Option<usize> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Where a ghost is after `t` steps is fixed by the state (node, instruction
+/// index), so every walk ends up in a cycle. Steps before `prefix` are walked
+/// once, after that the walk repeats every `period` steps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GhostCycle {
+    pub start: Parent,
+    pub prefix: usize,
+    pub period: usize,
+    // steps before the cycle that land on a ..Z node
+    pub prefix_hits: Vec<usize>,
+    // steps in prefix..prefix + period that land on a ..Z node, repeating every period
+    pub cycle_hits: Vec<usize>,
+}
+
+impl GhostCycle {
+    pub fn detect(start: &Parent, directions: &[Direction], map: &Map) -> Result<Self, String> {
+        if directions.is_empty() {
+            return Err("no directions to follow".to_string());
+        }
+        let mut first_seen: HashMap<(Parent, usize), usize> = HashMap::new();
+        let mut hits = Vec::new();
+        let mut node = *start;
+        let mut t = 0;
+        loop {
+            let instruction = t % directions.len();
+            if let Some(seen) = first_seen.insert((node, instruction), t) {
+                let (prefix_hits, cycle_hits) = hits.iter().partition(|hit| **hit < seen);
+                return Ok(GhostCycle {
+                    start: *start,
+                    prefix: seen,
+                    period: t - seen,
+                    prefix_hits,
+                    cycle_hits,
+                });
+            }
+            if is_end(&node) {
+                hits.push(t);
+            }
+            node = step(&node, &directions[instruction], map)?;
+            t += 1;
+        }
+    }
+
+    /// Whether the ghost is on a ..Z node after `t` steps.
+    pub fn hits_at(&self, t: usize) -> bool {
+        if t < self.prefix {
+            return self.prefix_hits.contains(&t);
+        }
+        let in_cycle = self.prefix + (t - self.prefix) % self.period;
+        self.cycle_hits.contains(&in_cycle)
+    }
+
+    pub fn first_hit(&self) -> Option<usize> {
+        self.prefix_hits
+            .iter()
+            .chain(self.cycle_hits.iter())
+            .copied()
+            .find(|hit| *hit > 0)
+    }
+
+    /// The LCM shortcut only holds when the ghost lands on ..Z exactly at
+    /// every multiple of its period and nowhere else.
+    pub fn lcm_friendly(&self) -> bool {
+        self.prefix_hits.iter().all(|hit| *hit == 0) && self.cycle_hits == [self.period]
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Method {
+    Lcm,
+    Crt,
+}
+
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        return (a, 1, 0);
+    }
+    let (g, x, y) = extended_gcd(b, a % b);
+    (g, y, x - (a / b) * y)
+}
+
+/// Combines t = a1 mod n1 and t = a2 mod n2 into one congruence, for moduli
+/// that need not be coprime.
+fn crt_pair((a1, n1): (i128, i128), (a2, n2): (i128, i128)) -> Option<(i128, i128)> {
+    let (g, p, _) = extended_gcd(n1, n2);
+    if (a2 - a1) % g != 0 {
+        return None;
+    }
+    let modulus = n1 / g * n2;
+    let k = ((a2 - a1) / g * p).rem_euclid(n2 / g);
+    Some(((a1 + n1 * k).rem_euclid(modulus), modulus))
+}
+
+/// Smallest step that lands in the cycle hits of every ghost.
+fn solve_cycles(cycles: &[GhostCycle]) -> Option<usize> {
+    let mut best: Option<usize> = None;
+    // pick one cycle hit per ghost, in every combination
+    let mut choice = vec![0; cycles.len()];
+    if cycles.iter().any(|cycle| cycle.cycle_hits.is_empty()) {
+        return None;
+    }
+    loop {
+        let offsets = cycles
+            .iter()
+            .zip(choice.iter())
+            .map(|(cycle, i)| cycle.cycle_hits[*i])
+            .collect::<Vec<usize>>();
+        let combined = cycles
+            .iter()
+            .zip(offsets.iter())
+            .try_fold((0i128, 1i128), |acc, (cycle, offset)| {
+                crt_pair(acc, (*offset as i128, cycle.period as i128))
+            });
+        if let Some((remainder, modulus)) = combined {
+            // every offset is already past its ghost's prefix, and step 0 doesn't count
+            let earliest = (*offsets.iter().max().unwrap()).max(1) as i128;
+            let mut t = remainder;
+            if t < earliest {
+                t += (earliest - t + modulus - 1) / modulus * modulus;
+            }
+            if let Ok(t) = usize::try_from(t) {
+                best = Some(best.map_or(t, |best| best.min(t)));
+            }
+        }
+
+        // next combination
+        let mut i = 0;
+        loop {
+            if i == cycles.len() {
+                return best;
+            }
+            choice[i] += 1;
+            if choice[i] < cycles[i].cycle_hits.len() {
+                break;
+            }
+            choice[i] = 0;
+            i += 1;
+        }
+    }
+}
+
+/// First step (after the start) where every ghost is on a ..Z node, and how it
+/// was found. `None` if the ghosts never line up, or not until a step past
+/// usize::MAX.
+pub fn solve_ghosts(cycles: &[GhostCycle]) -> Option<(usize, Method)> {
+    if cycles.is_empty() {
+        return None;
+    }
+    if cycles.iter().all(GhostCycle::lcm_friendly) {
+        let steps = cycles
+            .iter()
+            .try_fold(1, |acc, cycle| lcm(acc, cycle.period))?;
+        return Some((steps, Method::Lcm));
+    }
+
+    // hits before a cycle only happen once, so just try them all
+    let prefix_solution = cycles
+        .iter()
+        .flat_map(|cycle| cycle.prefix_hits.iter())
+        .copied()
+        .filter(|t| *t > 0 && cycles.iter().all(|cycle| cycle.hits_at(*t)))
+        .min();
+    let cycle_solution = solve_cycles(cycles);
+    let steps = match (prefix_solution, cycle_solution) {
+        (Some(a), Some(b)) => a.min(b),
+        (a, b) => a.or(b)?,
+    };
+    Some((steps, Method::Crt))
+}
+
+/// Steps from `start` to the first node matching `is_target`, or `None` if the
+/// walk starts repeating (node, instruction index) states before finding one.
+/// Always terminates, unlike stepping until a target turns up.
+pub fn search_length(
+    start: &Parent,
+    directions: &[Direction],
+    map: &Map,
+    is_target: impl Fn(&Parent) -> bool,
+) -> Result<Option<usize>, String> {
+    if directions.is_empty() {
+        return Err("no directions to follow".to_string());
+    }
+    let mut seen = HashSet::new();
+    let mut current_node = *start;
+    let mut step_count = 0;
+    while seen.insert((current_node, step_count % directions.len())) {
+        current_node = step(
+            &current_node,
+            &directions[step_count % directions.len()],
+            map,
+        )?;
+        step_count += 1;
+        if is_target(&current_node) {
+            return Ok(Some(step_count));
+        }
+    }
+    Ok(None)
+}
+
+/// Every node reachable from `starts` by any mix of left and right turns,
+/// ignoring the instruction string.
+pub fn reachable_from(starts: &[Parent], map: &Map) -> HashSet<Parent> {
+    let mut reached: HashSet<Parent> = starts.iter().copied().collect();
+    let mut to_visit = starts.to_vec();
+    while let Some(node) = to_visit.pop() {
+        if let Some((left, right)) = map.get(&node) {
+            for child in [left, right] {
+                if reached.insert(*child) {
+                    to_visit.push(*child);
+                }
+            }
+        }
+    }
+    reached
+}
+
+pub fn start_nodes(map: &Map) -> Vec<Parent> {
+    let mut starts = map
+        .keys()
+        .filter(|k| is_start(k))
+        .copied()
+        .collect::<Vec<_>>();
+    starts.sort();
+    starts
+}
+
+/// Nodes in the map that no ..A start can ever get to, sorted.
+pub fn unreachable_nodes(map: &Map) -> Vec<Parent> {
+    let reached = reachable_from(&start_nodes(map), map);
+    let mut unreachable = map
+        .keys()
+        .filter(|node| !reached.contains(*node))
+        .copied()
+        .collect::<Vec<_>>();
+    unreachable.sort();
+    unreachable
+}
+
+/// Graphviz dot for the network. Starts are green, ends are red and the
+/// edges are labelled with the turn that takes them.
+pub fn to_dot(map: &Map) -> String {
+    let mut nodes = map.keys().copied().collect::<Vec<_>>();
+    nodes.sort();
+    let mut dot = String::from("digraph network {\n");
+    for node in nodes.iter() {
+        let name = node_name(node);
+        if is_start(node) {
+            dot += &format!("    \"{name}\" [style=filled, fillcolor=green];\n");
+        } else if is_end(node) {
+            dot += &format!("    \"{name}\" [style=filled, fillcolor=red];\n");
+        }
+    }
+    for node in nodes.iter() {
+        let (left, right) = map[node];
+        let name = node_name(node);
+        if left == right {
+            dot += &format!(
+                "    \"{name}\" -> \"{}\" [label=\"LR\"];\n",
+                node_name(&left)
+            );
+        } else {
+            dot += &format!(
+                "    \"{name}\" -> \"{}\" [label=\"L\"];\n",
+                node_name(&left)
+            );
+            dot += &format!(
+                "    \"{name}\" -> \"{}\" [label=\"R\"];\n",
+                node_name(&right)
+            );
+        }
+    }
+    dot + "}\n"
+}
+
+pub fn parse_directions(input: &str) -> IResult<&str, Vec<Direction>> {
+    let (input, direction_str) = alpha1(input)?;
+    let directions = direction_str
+        .chars()
+        .map(|c| match c {
+            'L' => Direction::L,
+            'R' => Direction::R,
+            _ => panic!("Invalid direction"),
+        })
+        .collect();
+    Ok((input, directions))
+}
+
+pub fn parse_node_name(input: &str) -> IResult<&str, Parent> {
+    let mut parent = ['A'; 3];
+    let (input, parent_str) = alphanumeric1(input)?;
+    for (i, c) in parent_str.chars().enumerate() {
+        parent[i] = c;
+    }
+
+    Ok((input, parent))
+}
+
+pub fn parse_children(input: &str) -> IResult<&str, Children> {
+    delimited(
+        tag("("),
+        separated_pair(parse_node_name, tag(", "), parse_node_name),
+        tag(")"),
+    )(input)
+}
+
+pub fn parse_node(input: &str) -> IResult<&str, (Parent, Children)> {
+    separated_pair(parse_node_name, tag(" = "), parse_children)(input)
+}
+
+fn parse_nodes(input: &str) -> IResult<&str, Vec<(Parent, Children)>> {
+    separated_list1(newline, parse_node)(input)
+}
+
+pub fn parse_input(input: &str) -> IResult<&str, (Vec<Direction>, Map)> {
+    let (input, directions) = parse_directions(input)?;
+    let (input, _) = tuple((newline, newline))(input)?;
+    let mut map = HashMap::new();
+    let (_, nodes) = parse_nodes(input)?;
+    for (parent, children) in nodes {
+        map.insert(parent, children);
+    }
+    Ok(("", (directions, map)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_test_input() -> String {
+        "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)"
+            .to_string()
+    }
+
+    fn cycles(input: &str) -> Vec<GhostCycle> {
+        let (_, (directions, map)) = parse_input(input).unwrap();
+        start_nodes(&map)
+            .iter()
+            .map(|start| GhostCycle::detect(start, &directions, &map).unwrap())
+            .collect()
+    }
+
+    // step every ghost together until they all sit on ..Z
+    fn brute_force(input: &str, limit: usize) -> Option<usize> {
+        let (_, (directions, map)) = parse_input(input).unwrap();
+        let mut nodes = map
+            .keys()
+            .filter(|k| is_start(k))
+            .copied()
+            .collect::<Vec<_>>();
+        for t in 1..=limit {
+            let direction = &directions[(t - 1) % directions.len()];
+            for node in nodes.iter_mut() {
+                *node = step(node, direction, &map).unwrap();
+            }
+            if nodes.iter().all(is_end) {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_parse_directions() {
+        let directions = vec![Direction::L, Direction::R];
+        let dir_str = "LR";
+        assert_eq!(parse_directions(dir_str), Ok(("", directions)));
+    }
+
+    #[test]
+    fn test_parse_node_name() {
+        let parent_str = "11A";
+        assert_eq!(parse_node_name(parent_str), Ok(("", ['1', '1', 'A'])));
+    }
+
+    #[test]
+    fn test_parse_children() {
+        let children_str = "(11B, XXX)";
+        assert_eq!(
+            parse_children(children_str),
+            Ok(("", (['1', '1', 'B'], ['X', 'X', 'X'])))
+        );
+    }
+
+    #[test]
+    fn test_parse_node() {
+        let node_str = "22B = (22C, 22C)";
+        assert_eq!(
+            parse_node(node_str),
+            Ok(("", (['2', '2', 'B'], (['2', '2', 'C'], ['2', '2', 'C']))))
+        );
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let (_, (directions, map)) = parse_input(&make_test_input()).unwrap();
+        assert_eq!(directions, vec![Direction::L, Direction::R]);
+        assert_eq!(map.len(), 8);
+    }
+
+    #[test]
+    fn test_detect_cycles() {
+        let cycles = cycles(&make_test_input());
+        assert_eq!(
+            cycles[0],
+            GhostCycle {
+                start: ['1', '1', 'A'],
+                prefix: 1,
+                period: 2,
+                prefix_hits: vec![],
+                cycle_hits: vec![2],
+            }
+        );
+        // 22B -> 22C -> 22Z -> 22B repeats every 3 steps, but the
+        // instructions repeat every 2, so the state repeats every 6
+        assert_eq!(cycles[1].prefix, 1);
+        assert_eq!(cycles[1].period, 6);
+        assert_eq!(cycles[1].cycle_hits, vec![3, 6]);
+        assert!(cycles[1].hits_at(9));
+        assert!(!cycles[1].hits_at(10));
+    }
+
+    #[test]
+    fn test_example_falls_back_to_crt() {
+        let cycles = cycles(&make_test_input());
+        assert!(!cycles[1].lcm_friendly());
+        assert_eq!(solve_ghosts(&cycles), Some((6, Method::Crt)));
+    }
+
+    #[test]
+    fn test_lcm_fast_path() {
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)";
+        // 11Z hits every step and 22Z every 2nd step, both from the first cycle
+        let cycles = cycles(input);
+        assert!(cycles.iter().all(GhostCycle::lcm_friendly));
+        assert_eq!(solve_ghosts(&cycles), Some((2, Method::Lcm)));
+        assert_eq!(brute_force(input, 100), Some(2));
+    }
+
+    #[test]
+    fn test_lcm_overflow() {
+        assert_eq!(lcm(4, 6), Some(12));
+        // the product overflows but the lcm itself fits
+        assert_eq!(lcm(usize::MAX, usize::MAX), Some(usize::MAX));
+        assert_eq!(lcm(usize::MAX, 2), None);
+    }
+
+    #[test]
+    fn test_offset_cycles() {
+        // ghost 1 hits at 2, 5, 8, ...; ghost 2 at 3, 7, 11, ...
+        let input = "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11C, 11C)
+11C = (11D, 11D)
+11D = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22D, 22D)
+22D = (22E, 22E)
+22E = (22F, 22F)
+22F = (22Z, 22Z)";
+        let cycles = cycles(input);
+        assert_eq!(cycles[0].prefix_hits, vec![]);
+        assert_eq!(cycles[0].cycle_hits, vec![2]);
+        assert_eq!(cycles[0].period, 3);
+        assert_eq!(solve_ghosts(&cycles), Some((11, Method::Crt)));
+        assert_eq!(brute_force(input, 100), Some(11));
+    }
+
+    #[test]
+    fn test_prefix_hit() {
+        // ghost 1 only hits once at step 1, ghost 2 hits every step
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22Z, 22Z)";
+        let cycles = cycles(input);
+        assert_eq!(cycles[0].prefix_hits, vec![1]);
+        assert!(cycles[0].cycle_hits.is_empty());
+        assert_eq!(solve_ghosts(&cycles), Some((1, Method::Crt)));
+    }
+
+    #[test]
+    fn test_no_common_step() {
+        // both ghosts alternate, but out of phase
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)";
+        let cycles = cycles(input);
+        assert_eq!(solve_ghosts(&cycles), None);
+        assert_eq!(brute_force(input, 100), None);
+    }
+
+    #[test]
+    fn test_missing_node() {
+        let (_, (directions, map)) = parse_input("L\n\n11A = (11B, 11B)").unwrap();
+        assert!(GhostCycle::detect(&['1', '1', 'A'], &directions, &map).is_err());
+    }
+
+    #[test]
+    fn test_search_length() {
+        let (_, (directions, map)) = parse_input(&make_test_input()).unwrap();
+        let length = search_length(&['2', '2', 'A'], &directions, &map, is_end).unwrap();
+        assert_eq!(length, Some(3));
+        // XXX only loops back to itself
+        let length = search_length(&['X', 'X', 'X'], &directions, &map, is_end).unwrap();
+        assert_eq!(length, None);
+    }
+
+    #[test]
+    fn test_unreachable_nodes() {
+        let input = make_test_input() + "\n33B = (11A, 44Z)\n44Z = (44Z, 44Z)";
+        let (_, (_, map)) = parse_input(&input).unwrap();
+        assert_eq!(
+            unreachable_nodes(&map),
+            vec![['3', '3', 'B'], ['4', '4', 'Z']]
+        );
+        let reached = reachable_from(&[['1', '1', 'A']], &map);
+        assert_eq!(reached.len(), 4);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let (_, (_, map)) = parse_input("L\n\n11A = (11Z, 22B)\n11Z = (11Z, 11Z)").unwrap();
+        assert_eq!(
+            to_dot(&map),
+            "digraph network {
+    \"11A\" [style=filled, fillcolor=green];
+    \"11Z\" [style=filled, fillcolor=red];
+    \"11A\" -> \"11Z\" [label=\"L\"];
+    \"11A\" -> \"22B\" [label=\"R\"];
+    \"11Z\" -> \"11Z\" [label=\"LR\"];
+}
+"
+        );
+    }
+}
@@ -1,41 +1,27 @@
-use nom::bytes::complete::tag;
-use nom::character::complete::i64;
-use nom::multi::separated_list1;
-use nom::IResult;
+use day_09::{solve_report, Backend, Horizon};
 use std::env;
 use std::fs;
 
+// usage: part1 <filepath> [steps] [--backend=i64|i128|big|auto]
 fn main() {
     let args: Vec<String> = env::args().collect();
     let input = fs::read_to_string(&args[1]).expect("Error reading input file");
-    let (_, sequences) = parse_input(&input).unwrap();
-    let solution = get_solution(sequences);
-    println!("Solution: {}", solution);
-}
-
-fn get_solution(sequences: Vec<Vec<isize>>) -> isize {
-    sequences.iter().map(|seq| solve_seq(seq)).sum()
-}
-
-fn solve_seq(seq: &Vec<isize>) -> isize {
-    if seq.iter().all(|x| *x == 0) {
-        return 0;
+    let mut steps = 1;
+    let mut backend = Backend::Auto;
+    for arg in &args[2..] {
+        match arg.strip_prefix("--backend=") {
+            Some(name) => backend = name.parse().unwrap_or_else(|err| panic!("{err}")),
+            None => steps = arg.parse::<usize>().expect("steps should be a number"),
+        }
+    }
+    match get_solution(&input, steps, backend) {
+        Ok(solution) => println!("Solution: {}", solution),
+        Err(message) => println!("{message}"),
     }
-    let diffs = seq
-        .windows(2)
-        .map(|window| window[1] - window[0])
-        .collect::<Vec<isize>>();
-
-    seq.last().unwrap() + solve_seq(&diffs)
 }
 
-fn parse_seq(input: &str) -> IResult<&str, Vec<isize>> {
-    let (input, nums) = separated_list1(tag(" "), i64)(input)?;
-    let nums = nums.iter().map(|x| *x as isize).collect::<Vec<isize>>();
-    Ok((input, nums))
-}
-fn parse_input(input: &str) -> IResult<&str, Vec<Vec<isize>>> {
-    separated_list1(tag("\n"), parse_seq)(input)
+fn get_solution(input: &str, steps: usize, backend: Backend) -> Result<String, String> {
+    solve_report(input, Horizon::Forward(steps), backend)
 }
 
 #[cfg(test)]
@@ -44,19 +30,28 @@ mod tests {
 
     #[test]
     fn solve_seq_ex1() {
-        let seq = vec![0, 3, 6, 9, 12, 15];
-        assert_eq!(solve_seq(&seq), 18);
+        let seq = "0 3 6 9 12 15";
+        assert_eq!(get_solution(seq, 1, Backend::I64).unwrap(), "18");
     }
 
     #[test]
     fn solve_seq_ex2() {
-        let seq = vec![1, 3, 6, 10, 15, 21];
-        assert_eq!(solve_seq(&seq), 28);
+        let seq = "1 3 6 10 15 21";
+        assert_eq!(get_solution(seq, 1, Backend::I64).unwrap(), "28");
     }
 
     #[test]
     fn solve_seq_ex3() {
-        let seq = vec![10, 13, 16, 21, 30, 45];
-        assert_eq!(solve_seq(&seq), 68);
+        let seq = "10 13 16 21 30 45";
+        assert_eq!(get_solution(seq, 1, Backend::I64).unwrap(), "68");
+    }
+
+    #[test]
+    fn test_flags_non_polynomial() {
+        let sequences = "0 3 6\n1 2 4 8";
+        assert_eq!(
+            get_solution(sequences, 1, Backend::Auto),
+            Err("sequence 2: not a polynomial within its 4 values".to_string())
+        );
     }
 }
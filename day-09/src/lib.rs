@@ -0,0 +1,426 @@
+use nom::character::complete::{char, digit1, space1};
+use nom::combinator::{map_res, opt, recognize};
+use nom::multi::separated_list1;
+use nom::sequence::pair;
+use nom::IResult;
+use num_bigint::{BigInt, ToBigInt};
+use num_rational::Ratio;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, FromPrimitive, One, Zero};
+use std::fmt;
+use std::str::FromStr;
+
+/// What a sequence value has to support. Every operation on readings is
+/// checked, so `i64` or `i128` report overflow instead of wrapping, and
+/// `BigInt` never overflows.
+pub trait Reading:
+    Clone
+    + PartialEq
+    + fmt::Debug
+    + fmt::Display
+    + FromStr
+    + Zero
+    + One
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+    + CheckedDiv
+    + FromPrimitive
+    + ToBigInt
+{
+}
+
+impl<T> Reading for T where
+    T: Clone
+        + PartialEq
+        + fmt::Debug
+        + fmt::Display
+        + FromStr
+        + Zero
+        + One
+        + CheckedAdd
+        + CheckedSub
+        + CheckedMul
+        + CheckedDiv
+        + FromPrimitive
+        + ToBigInt
+{
+}
+
+fn parse_reading<T: Reading>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse::<T>)(input)
+}
+
+pub fn parse_seq<T: Reading>(input: &str) -> IResult<&str, Vec<T>> {
+    separated_list1(space1, parse_reading)(input)
+}
+
+pub fn parse_input<T: Reading>(input: &str) -> IResult<&str, Vec<Vec<T>>> {
+    separated_list1(char('\n'), parse_seq)(input)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FitError {
+    Empty,
+    // the differences never settle into a constant row while there are still
+    // at least two values to compare, so any polynomial of degree len - 1 fits
+    NotPolynomial { len: usize },
+    // the reading type is too small for the differences or the extrapolation
+    Overflow,
+}
+
+impl fmt::Display for FitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FitError::Empty => write!(f, "empty sequence"),
+            FitError::NotPolynomial { len } => {
+                write!(f, "not a polynomial within its {len} values")
+            }
+            FitError::Overflow => write!(f, "overflowed the reading type"),
+        }
+    }
+}
+
+fn from_isize<T: Reading>(x: isize) -> Result<T, FitError> {
+    T::from_isize(x).ok_or(FitError::Overflow)
+}
+
+/// The lowest degree polynomial through a sequence, where the first value is
+/// at x = 0. Stored in Newton form: `differences[k]` is the first value of
+/// the k-th difference row, and p(x) = sum of differences[k] * C(x, k).
+#[derive(Debug, PartialEq, Clone)]
+pub struct Polynomial<T> {
+    differences: Vec<T>,
+    len: usize,
+}
+
+impl<T: Reading> Polynomial<T> {
+    pub fn fit(seq: &[T]) -> Result<Polynomial<T>, FitError> {
+        if seq.is_empty() {
+            return Err(FitError::Empty);
+        }
+        let mut differences = Vec::new();
+        let mut row = seq.to_vec();
+        // a row has to be constant over at least two values to count
+        while row.len() >= 2 {
+            differences.push(row[0].clone());
+            if row.iter().all(|x| *x == row[0]) {
+                return Ok(Polynomial {
+                    differences,
+                    len: seq.len(),
+                });
+            }
+            row = row
+                .windows(2)
+                .map(|window| window[1].checked_sub(&window[0]).ok_or(FitError::Overflow))
+                .collect::<Result<Vec<T>, FitError>>()?;
+        }
+        Err(FitError::NotPolynomial { len: seq.len() })
+    }
+
+    pub fn degree(&self) -> usize {
+        self.differences.len() - 1
+    }
+
+    /// Value at any integer x, including before the start of the sequence.
+    pub fn value_at(&self, x: isize) -> Result<T, FitError> {
+        // C(x, k) = C(x, k - 1) * (x - k + 1) / k stays exact for negative x too
+        let mut binomial = T::one();
+        let mut value = T::zero();
+        for (k, difference) in self.differences.iter().enumerate() {
+            if k > 0 {
+                let factor = x.checked_sub(k as isize - 1).ok_or(FitError::Overflow)?;
+                binomial = binomial
+                    .checked_mul(&from_isize(factor)?)
+                    .and_then(|product| product.checked_div(&T::from_usize(k)?))
+                    .ok_or(FitError::Overflow)?;
+            }
+            value = difference
+                .checked_mul(&binomial)
+                .and_then(|term| value.checked_add(&term))
+                .ok_or(FitError::Overflow)?;
+        }
+        Ok(value)
+    }
+
+    /// The value `steps` places after the last one.
+    pub fn forward(&self, steps: usize) -> Result<T, FitError> {
+        let x = (self.len - 1)
+            .checked_add(steps)
+            .and_then(|x| isize::try_from(x).ok())
+            .ok_or(FitError::Overflow)?;
+        self.value_at(x)
+    }
+
+    /// The value `steps` places before the first one.
+    pub fn backward(&self, steps: usize) -> Result<T, FitError> {
+        let x = isize::try_from(steps).map_err(|_| FitError::Overflow)?;
+        self.value_at(-x)
+    }
+
+    /// Coefficients of x^0, x^1, ... x^degree. Always worked out over
+    /// `BigInt` since the factorials outgrow the readings long before the
+    /// values do.
+    pub fn coefficients(&self) -> Vec<Ratio<BigInt>> {
+        let mut coefficients = vec![Ratio::from_integer(BigInt::zero()); self.differences.len()];
+        // falling factorial x (x - 1) ... (x - k + 1), lowest power first
+        let mut falling: Vec<BigInt> = vec![BigInt::one()];
+        let mut factorial = BigInt::one();
+        for (k, difference) in self.differences.iter().enumerate() {
+            if k > 0 {
+                let shift = BigInt::from(k - 1);
+                let mut next = vec![BigInt::zero(); falling.len() + 1];
+                for (power, coefficient) in falling.iter().enumerate() {
+                    next[power + 1] += coefficient;
+                    next[power] -= coefficient * &shift;
+                }
+                falling = next;
+                factorial *= k;
+            }
+            let difference = difference.to_bigint().expect("readings are integers");
+            let scale = Ratio::new(difference, factorial.clone());
+            for (power, coefficient) in falling.iter().enumerate() {
+                coefficients[power] += &scale * coefficient;
+            }
+        }
+        coefficients
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Horizon {
+    Forward(usize),
+    Backward(usize),
+}
+
+impl<T: Reading> Polynomial<T> {
+    pub fn extrapolate(&self, horizon: Horizon) -> Result<T, FitError> {
+        match horizon {
+            Horizon::Forward(steps) => self.forward(steps),
+            Horizon::Backward(steps) => self.backward(steps),
+        }
+    }
+}
+
+/// Sum of every sequence extrapolated to `horizon`. Errors carry the
+/// (1 based) line of the sequence that failed.
+pub fn sum_extrapolations<T: Reading>(
+    sequences: &[Vec<T>],
+    horizon: Horizon,
+) -> Result<T, (usize, FitError)> {
+    let mut solution = T::zero();
+    for (i, seq) in sequences.iter().enumerate() {
+        let value = Polynomial::fit(seq)
+            .and_then(|fit| fit.extrapolate(horizon))
+            .map_err(|err| (i + 1, err))?;
+        solution = solution
+            .checked_add(&value)
+            .ok_or((i + 1, FitError::Overflow))?;
+    }
+    Ok(solution)
+}
+
+/// Which integer type to do the arithmetic in. `Auto` starts with `i64` and
+/// switches to `BigInt` if the readings don't fit or anything overflows.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Backend {
+    I64,
+    I128,
+    Big,
+    Auto,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Backend, String> {
+        match name {
+            "i64" => Ok(Backend::I64),
+            "i128" => Ok(Backend::I128),
+            "big" => Ok(Backend::Big),
+            "auto" => Ok(Backend::Auto),
+            _ => Err(format!(
+                "unknown backend {name:?}, use i64, i128, big or auto"
+            )),
+        }
+    }
+}
+
+fn solve_with<T: Reading>(input: &str, horizon: Horizon) -> Result<String, (bool, String)> {
+    let (rest, sequences) = parse_input::<T>(input.trim_end())
+        .map_err(|err| (true, format!("could not read the report: {err}")))?;
+    if !rest.is_empty() {
+        // usually a reading that doesn't fit in T
+        return Err((true, format!("could not read the report from {rest:?}")));
+    }
+    sum_extrapolations(&sequences, horizon)
+        .map(|solution| solution.to_string())
+        .map_err(|(line, err)| (err == FitError::Overflow, format!("sequence {line}: {err}")))
+}
+
+/// Parses and extrapolates a whole report with the chosen backend.
+pub fn solve_report(input: &str, horizon: Horizon, backend: Backend) -> Result<String, String> {
+    let result = match backend {
+        Backend::I64 => solve_with::<i64>(input, horizon),
+        Backend::I128 => solve_with::<i128>(input, horizon),
+        Backend::Big => solve_with::<BigInt>(input, horizon),
+        Backend::Auto => match solve_with::<i64>(input, horizon) {
+            Err((true, _)) => solve_with::<BigInt>(input, horizon),
+            result => result,
+        },
+    };
+    result.map_err(|(_, message)| message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_test_input() -> String {
+        "0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45"
+            .to_string()
+    }
+
+    #[test]
+    fn test_example_forward_and_backward() {
+        let (_, sequences) = parse_input::<isize>(&make_test_input()).unwrap();
+        let fits = sequences
+            .iter()
+            .map(|seq| Polynomial::fit(seq).unwrap())
+            .collect::<Vec<_>>();
+        let forward = fits
+            .iter()
+            .map(|fit| fit.forward(1).unwrap())
+            .collect::<Vec<_>>();
+        let backward = fits
+            .iter()
+            .map(|fit| fit.backward(1).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(forward, vec![18, 28, 68]);
+        assert_eq!(backward, vec![-3, 0, 5]);
+        let degrees = fits.iter().map(Polynomial::degree).collect::<Vec<_>>();
+        assert_eq!(degrees, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_far_horizons() {
+        // squares, starting at 0
+        let fit = Polynomial::fit(&[0, 1, 4, 9]).unwrap();
+        assert_eq!(fit.forward(10), Ok(13 * 13));
+        assert_eq!(fit.backward(7), Ok(49));
+        assert_eq!(fit.value_at(2), Ok(4));
+    }
+
+    #[test]
+    fn test_coefficients() {
+        let ratio = |n: i64, d: i64| Ratio::new(BigInt::from(n), BigInt::from(d));
+        // 1 3 6 10 15 21 is (x + 1)(x + 2) / 2 = 1 + 3/2 x + 1/2 x^2
+        let fit = Polynomial::fit(&[1, 3, 6, 10, 15, 21]).unwrap();
+        assert_eq!(
+            fit.coefficients(),
+            vec![ratio(1, 1), ratio(3, 2), ratio(1, 2)]
+        );
+        let fit = Polynomial::fit(&[10, 13, 16, 21, 30, 45]).unwrap();
+        let coefficients = fit.coefficients();
+        for x in -5..10i64 {
+            let value = coefficients
+                .iter()
+                .rev()
+                .fold(ratio(0, 1), |acc, c| acc * BigInt::from(x) + c);
+            assert_eq!(value, ratio(fit.value_at(x as isize).unwrap(), 1));
+        }
+    }
+
+    #[test]
+    fn test_constant_and_zero() {
+        let fit = Polynomial::fit(&[7, 7]).unwrap();
+        assert_eq!(fit.degree(), 0);
+        assert_eq!(fit.backward(3), Ok(7));
+        let fit = Polynomial::fit(&[0, 0, 0]).unwrap();
+        assert_eq!(fit.forward(1), Ok(0));
+    }
+
+    #[test]
+    fn test_not_polynomial() {
+        // powers of two never settle
+        assert_eq!(
+            Polynomial::fit(&[1, 2, 4, 8, 16]),
+            Err(FitError::NotPolynomial { len: 5 })
+        );
+        // a single value could be anything
+        assert_eq!(
+            Polynomial::fit(&[3]),
+            Err(FitError::NotPolynomial { len: 1 })
+        );
+        assert_eq!(Polynomial::<i64>::fit(&[]), Err(FitError::Empty));
+    }
+
+    #[test]
+    fn test_checked_overflow() {
+        // the first difference alone doesn't fit in an i64
+        let seq = [i64::MIN, i64::MAX, 0];
+        assert_eq!(Polynomial::fit(&seq), Err(FitError::Overflow));
+        // the same straight line is fine in an i128
+        let (start, step) = (i64::MIN as i128, u64::MAX as i128);
+        let seq = [start, start + step, start + 2 * step];
+        let fit = Polynomial::fit(&seq).unwrap();
+        assert_eq!(fit.forward(1), Ok(start + 3 * step));
+
+        // fits, but the next value is past i64::MAX
+        let fit = Polynomial::fit(&[0, i64::MAX / 2, i64::MAX - 1]).unwrap();
+        assert_eq!(fit.forward(1), Err(FitError::Overflow));
+
+        // step counts that don't fit in an isize
+        let fit = Polynomial::fit(&[1i64, 2, 3]).unwrap();
+        assert_eq!(fit.forward(usize::MAX), Err(FitError::Overflow));
+        assert_eq!(fit.forward(isize::MAX as usize), Err(FitError::Overflow));
+        assert_eq!(fit.backward(usize::MAX), Err(FitError::Overflow));
+        let fit = Polynomial::fit(&[1i64, 4, 9, 16]).unwrap();
+        assert_eq!(fit.value_at(isize::MIN), Err(FitError::Overflow));
+    }
+
+    #[test]
+    fn test_backends() {
+        let input = make_test_input();
+        for backend in [Backend::I64, Backend::I128, Backend::Big, Backend::Auto] {
+            assert_eq!(
+                solve_report(&input, Horizon::Forward(1), backend),
+                Ok("114".to_string())
+            );
+            assert_eq!(
+                solve_report(&input, Horizon::Backward(1), backend),
+                Ok("2".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_big_readings() {
+        // readings past i128, a straight line with step 10^30
+        let input = "100000000000000000000000000000000000000000 \
+                     100000000001000000000000000000000000000000 \
+                     100000000002000000000000000000000000000000";
+        assert!(solve_report(input, Horizon::Forward(1), Backend::I128).is_err());
+        let expected = "100000000003000000000000000000000000000000".to_string();
+        assert_eq!(
+            solve_report(input, Horizon::Forward(1), Backend::Big),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            solve_report(input, Horizon::Forward(1), Backend::Auto),
+            Ok(expected)
+        );
+
+        // readings fit in i64 but the extrapolation doesn't
+        let input = "0 4611686018427387903 9223372036854775806";
+        assert_eq!(
+            solve_report(input, Horizon::Forward(1), Backend::I64),
+            Err("sequence 1: overflowed the reading type".to_string())
+        );
+        assert_eq!(
+            solve_report(input, Horizon::Forward(1), Backend::Auto),
+            Ok("13835058055282163709".to_string())
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use day_10::{fill_start, find_loops, parse_input, parse_map};
+use std::env;
+use std::fs;
+
+// usage: loops <filepath>
+// lists every closed loop in the map, the one through S included if S can be worked out
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let input = fs::read_to_string(&args[1]).expect("Error reading input file");
+    let (map, start_point) = match parse_input(&input) {
+        Ok((map, start_point)) => match fill_start(map, &start_point) {
+            Ok(map) => (map, Some(start_point)),
+            Err(err) => {
+                println!("{err}, leaving S out");
+                (parse_map(&input), None)
+            }
+        },
+        Err(err) => {
+            println!("{err}, leaving S out");
+            (parse_map(&input), None)
+        }
+    };
+    for pipe_loop in find_loops(&map) {
+        let first = pipe_loop.tiles[0];
+        let through_start = start_point.is_some_and(|start| pipe_loop.tiles.contains(&start));
+        let marker = if through_start { " (through S)" } else { "" };
+        println!(
+            "loop at ({}, {}){}: length {}, farthest {}, encloses {}",
+            first.i,
+            first.j,
+            marker,
+            pipe_loop.length(),
+            pipe_loop.farthest(),
+            pipe_loop.enclosed()
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use day_10::{classify_map, parse_input, render_ppm, render_text};
+use std::env;
+use std::fs;
+
+// usage: render <filepath> [--plain] [--ppm <out file> [scale]]
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let input = fs::read_to_string(&args[1]).expect("Error reading input file");
+    let (map, start_point) = match parse_input(&input) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            println!("{err}");
+            return;
+        }
+    };
+    let (map, tiles) = match classify_map(map, start_point) {
+        Ok(classified) => classified,
+        Err(message) => {
+            println!("{message}");
+            return;
+        }
+    };
+
+    match args.iter().position(|arg| arg == "--ppm") {
+        Some(flag) => {
+            let path = args.get(flag + 1).expect("--ppm needs a file to write to");
+            let scale = args
+                .get(flag + 2)
+                .map(|scale| scale.parse::<usize>().expect("scale should be a number"))
+                .unwrap_or(6);
+            fs::write(path, render_ppm(&map, &tiles, scale)).expect("Error writing image");
+            println!("wrote {path}");
+        }
+        None => {
+            let colour = !args.iter().any(|arg| arg == "--plain");
+            print!("{}", render_text(&map, &tiles, colour));
+        }
+    }
+}
@@ -0,0 +1,872 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Direction {
+    pub del_i: isize,
+    pub del_j: isize,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Pipe(pub &'static Direction, pub &'static Direction);
+
+pub static UP: Direction = Direction {
+    del_i: -1,
+    del_j: 0,
+};
+
+pub static DOWN: Direction = Direction { del_i: 1, del_j: 0 };
+
+pub static LEFT: Direction = Direction {
+    del_i: 0,
+    del_j: -1,
+};
+
+pub static RIGHT: Direction = Direction { del_i: 0, del_j: 1 };
+
+pub static PIPE_UP_DOWN: Pipe = Pipe(&UP, &DOWN);
+pub static PIPE_UP_LEFT: Pipe = Pipe(&UP, &LEFT);
+pub static PIPE_UP_RIGHT: Pipe = Pipe(&UP, &RIGHT);
+pub static PIPE_LEFT_RIGHT: Pipe = Pipe(&LEFT, &RIGHT);
+pub static PIPE_DOWN_LEFT: Pipe = Pipe(&DOWN, &LEFT);
+pub static PIPE_DOWN_RIGHT: Pipe = Pipe(&DOWN, &RIGHT);
+
+pub static PIPES: [&Pipe; 6] = [
+    &PIPE_UP_DOWN,
+    &PIPE_UP_LEFT,
+    &PIPE_UP_RIGHT,
+    &PIPE_LEFT_RIGHT,
+    &PIPE_DOWN_LEFT,
+    &PIPE_DOWN_RIGHT,
+];
+
+pub static SYMBOLS: [(char, &Pipe); 6] = [
+    ('J', &PIPE_UP_LEFT),
+    ('|', &PIPE_UP_DOWN),
+    ('L', &PIPE_UP_RIGHT),
+    ('F', &PIPE_DOWN_RIGHT),
+    ('-', &PIPE_LEFT_RIGHT),
+    ('7', &PIPE_DOWN_LEFT),
+];
+
+pub static OPPOSITES: [(&Direction, &Direction); 4] =
+    [(&UP, &DOWN), (&DOWN, &UP), (&LEFT, &RIGHT), (&RIGHT, &LEFT)];
+
+pub static DIRECTIONS: [&Direction; 4] = [&UP, &DOWN, &LEFT, &RIGHT];
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Point {
+    pub i: usize,
+    pub j: usize,
+}
+
+pub struct Map {
+    pub map: Vec<Vec<Option<&'static Pipe>>>,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl Map {
+    pub fn move_point(&self, point: &Point, direction: &Direction) -> Option<Point> {
+        let i = point.i as isize + direction.del_i;
+        if i < 0 || i >= self.rows as isize {
+            return None;
+        }
+        let j = point.j as isize + direction.del_j;
+        if j < 0 || j >= self.cols as isize {
+            return None;
+        }
+
+        Some(Point {
+            i: i as usize,
+            j: j as usize,
+        })
+    }
+
+    pub fn get_pipe(&self, point: &Point) -> Option<&Pipe> {
+        if let Some(row) = self.map.get(point.i) {
+            if let Some(element) = row.get(point.j) {
+                return *element;
+            }
+        }
+        None
+    }
+}
+
+/// The pipes in the map, with S left empty.
+pub fn parse_map(input: &str) -> Map {
+    let symbol_map = HashMap::from(SYMBOLS);
+    let map: Vec<Vec<Option<&Pipe>>> = input
+        .lines()
+        .map(|line| line.chars().map(|c| symbol_map.get(&c).copied()).collect())
+        .collect();
+    let rows = map.len();
+    let cols = map.first().map_or(0, |row| row.len());
+    Map { map, rows, cols }
+}
+
+/// The map and the position of S. The map needs exactly one S.
+pub fn parse_input(input: &str) -> Result<(Map, Point), StartError> {
+    let starts = input
+        .lines()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == 'S')
+                .map(move |(j, _)| Point { i, j })
+        })
+        .collect::<Vec<_>>();
+    let start_point = match starts[..] {
+        [start] => start,
+        [] => return Err(StartError::MissingStart),
+        _ => return Err(StartError::MultipleStarts { starts }),
+    };
+    Ok((parse_map(input), start_point))
+}
+
+pub fn get_first_moves(map: &Map, start_point: &Point) -> Vec<&'static Direction> {
+    let opposite_map = HashMap::from(OPPOSITES);
+    let mut start_directions = vec![];
+
+    for direction in DIRECTIONS.into_iter() {
+        if let Some(candidate_point) = map.move_point(start_point, direction) {
+            let moved_from = opposite_map.get(direction).unwrap();
+            if let Some(next_pipe) = map.get_pipe(&candidate_point) {
+                if next_pipe.0 == *moved_from || next_pipe.1 == *moved_from {
+                    start_directions.push(direction);
+                }
+            }
+        }
+    }
+    start_directions
+}
+
+/// Follows the pipes from `start`, treating the start tile as `start_pipe`.
+/// Every step has to land on a pipe that connects back. Gives the loop in
+/// walking order, or the tiles walked before the way ran out.
+fn walk(map: &Map, start: Point, start_pipe: &Pipe) -> Result<Vec<Point>, Vec<Point>> {
+    let opposite_map = HashMap::from(OPPOSITES);
+    let mut next_move = start_pipe.0;
+    let mut current_point = start;
+    let mut path: Vec<Point> = vec![current_point];
+    loop {
+        let moved_from = *opposite_map.get(next_move).unwrap();
+        let next_point = match map.move_point(&current_point, next_move) {
+            Some(next_point) => next_point,
+            None => return Err(path),
+        };
+        if next_point == start {
+            // we left through one end of the start pipe, so have to come back through the other
+            return if start_pipe.1 == moved_from {
+                Ok(path)
+            } else {
+                Err(path)
+            };
+        }
+        let next_pipe = match map.get_pipe(&next_point) {
+            Some(next_pipe) => next_pipe,
+            None => return Err(path),
+        };
+        next_move = if next_pipe.0 == moved_from {
+            next_pipe.1
+        } else if next_pipe.1 == moved_from {
+            next_pipe.0
+        } else {
+            return Err(path);
+        };
+        path.push(next_point);
+        current_point = next_point;
+        // a loop can't be longer than the map, this only guards against loops
+        // that don't pass through `start`, which connected pipes can't form
+        if path.len() > map.rows * map.cols {
+            return Err(path);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StartError {
+    // there is no S in the map
+    MissingStart,
+    // there is more than one S in the map
+    MultipleStarts { starts: Vec<Point> },
+    // fewer than two neighbours have a pipe pointing at S
+    TooFewConnections { start: Point, connections: usize },
+    // pipes connect to S, but no choice of pipe under S closes a loop
+    NoClosedLoop { start: Point },
+    // more than one choice of pipe under S closes a loop
+    Ambiguous { start: Point, pipes: Vec<char> },
+}
+
+impl fmt::Display for StartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartError::MissingStart => write!(f, "the map has no start tile S"),
+            StartError::MultipleStarts { starts } => write!(
+                f,
+                "the map has {} start tiles S, at {}",
+                starts.len(),
+                starts
+                    .iter()
+                    .map(|start| format!("({}, {})", start.i, start.j))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            StartError::TooFewConnections { start, connections } => write!(
+                f,
+                "start at ({}, {}) connects to {} pipes, it needs 2",
+                start.i, start.j, connections
+            ),
+            StartError::NoClosedLoop { start } => write!(
+                f,
+                "no pipe at the start ({}, {}) closes a loop",
+                start.i, start.j
+            ),
+            StartError::Ambiguous { start, pipes } => write!(
+                f,
+                "start at ({}, {}) could be any of {}",
+                start.i,
+                start.j,
+                pipes.iter().collect::<String>()
+            ),
+        }
+    }
+}
+
+fn symbol(pipe: &Pipe) -> char {
+    SYMBOLS
+        .iter()
+        .find(|(_, candidate)| *candidate == pipe)
+        .map(|(symbol, _)| *symbol)
+        .unwrap()
+}
+
+/// The pipe hidden under S: the only one that joins two of the pipes pointing
+/// at S into a closed loop.
+pub fn infer_start(map: &Map, start_point: &Point) -> Result<&'static Pipe, StartError> {
+    let first_moves = get_first_moves(map, start_point);
+    if first_moves.len() < 2 {
+        return Err(StartError::TooFewConnections {
+            start: *start_point,
+            connections: first_moves.len(),
+        });
+    }
+    let closing = PIPES
+        .iter()
+        .filter(|pipe| first_moves.contains(&pipe.0) && first_moves.contains(&pipe.1))
+        .filter(|pipe| walk(map, *start_point, pipe).is_ok())
+        .collect::<Vec<_>>();
+    match closing[..] {
+        [pipe] => Ok(pipe),
+        [] => Err(StartError::NoClosedLoop {
+            start: *start_point,
+        }),
+        _ => Err(StartError::Ambiguous {
+            start: *start_point,
+            pipes: closing.iter().map(|pipe| symbol(pipe)).collect(),
+        }),
+    }
+}
+
+/// The map with S replaced by the pipe under it.
+pub fn fill_start(mut map: Map, start_point: &Point) -> Result<Map, StartError> {
+    let pipe = infer_start(&map, start_point)?;
+    map.map[start_point.i][start_point.j] = Some(pipe);
+    Ok(map)
+}
+
+/// Every tile of the loop through `start_point`, in the order they are walked,
+/// starting with `start_point` itself.
+pub fn traverse(map: &Map, start_point: Point) -> Result<Vec<Point>, String> {
+    let start_pipe = infer_start(map, &start_point).map_err(|err| err.to_string())?;
+    walk(map, start_point, start_pipe)
+        .map_err(|_| format!("the loop through {:?} is not closed", start_point))
+}
+
+/// A closed loop of pipes, in walking order.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PipeLoop {
+    pub tiles: Vec<Point>,
+}
+
+impl PipeLoop {
+    pub fn length(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Steps to the tile farthest along the loop from its first tile.
+    pub fn farthest(&self) -> usize {
+        self.tiles.len() / 2
+    }
+
+    pub fn enclosed(&self) -> usize {
+        enclosed_tiles(&self.tiles)
+    }
+}
+
+/// Every closed loop in the map, ordered by their top left tile. S only takes
+/// part if it has been replaced by its pipe first (see `fill_start`).
+pub fn find_loops(map: &Map) -> Vec<PipeLoop> {
+    let mut seen = vec![vec![false; map.cols]; map.rows];
+    let mut loops = Vec::new();
+    for (i, row) in map.map.iter().enumerate() {
+        for (j, pipe) in row.iter().enumerate() {
+            let pipe = match pipe {
+                Some(pipe) if !seen[i][j] => pipe,
+                _ => continue,
+            };
+            // every step of a walk connects both ways, so tiles on a walk that
+            // doesn't close can't be on any loop either
+            let (tiles, closed) = match walk(map, Point { i, j }, pipe) {
+                Ok(tiles) => (tiles, true),
+                Err(tiles) => (tiles, false),
+            };
+            for point in tiles.iter() {
+                seen[point.i][point.j] = true;
+            }
+            if closed {
+                loops.push(PipeLoop { tiles });
+            }
+        }
+    }
+    loops
+}
+
+pub fn replace_start(mut map: Map, start_point: &Point, start_moves: Vec<&Direction>) -> Map {
+    // figure out what kind of pipe is needed
+    // only a straight choice with exactly two moves, `fill_start` works it out otherwise
+    let mut start_pipe = None;
+    if let [first, second] = start_moves[..] {
+        for pipe in PIPES.iter() {
+            if pipe.0 == first && pipe.1 == second || pipe.0 == second && pipe.1 == first {
+                start_pipe = Some(*pipe);
+            }
+        }
+    }
+
+    map.map[start_point.i][start_point.j] = start_pipe;
+
+    map
+}
+
+/// How we count the tiles enclosed by the loop.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Method {
+    /// Shoelace area of the loop, then Pick's theorem. Linear in the loop length.
+    Area,
+    /// Cast a ray from every tile off the loop and count the walls it crosses.
+    /// Much slower, kept to cross-check `Area`.
+    RayCast,
+}
+
+/// Tiles strictly inside the loop. `path` has to be every tile of the loop in
+/// walking order, like `traverse` returns.
+///
+/// The shoelace formula gives the area A of the polygon through the tile
+/// centres, and Pick's theorem A = inside + boundary / 2 - 1 turns that into
+/// the number of tiles inside, since every loop tile is a boundary point.
+pub fn enclosed_tiles(path: &[Point]) -> usize {
+    let twice_area = path
+        .iter()
+        .zip(path.iter().cycle().skip(1))
+        .map(|(a, b)| a.i as isize * b.j as isize - b.i as isize * a.j as isize)
+        .sum::<isize>()
+        .unsigned_abs();
+    // inside = A - b / 2 + 1, doubled so it stays in integers
+    (twice_area + 2).saturating_sub(path.len()) / 2
+}
+
+pub fn make_wall_counts(map: &Map, path: &[Point]) -> Vec<Vec<usize>> {
+    let mut wall_counts = vec![vec![0; map.cols]; map.rows];
+    for (i, (row, row_counts)) in map.map.iter().zip(wall_counts.iter_mut()).enumerate() {
+        for (j, cell_count) in row_counts.iter_mut().enumerate() {
+            // make a ray and count how many times it croses a wall
+            //count true
+            let mut wall_count = 0;
+            if !path.contains(&Point { i, j }) && j < map.cols - 1 {
+                let mut ray = j + 1;
+
+                'ray_tracer: while ray < map.cols {
+                    if let Some(pipe) = row[ray] {
+                        if !path.contains(&Point { i, j: ray }) {
+                            ray += 1;
+                            continue;
+                        }
+                        if pipe == &PIPE_UP_DOWN {
+                            wall_count += 1;
+                            ray += 1;
+                            continue;
+                        } else if pipe == &PIPE_DOWN_RIGHT || pipe == &PIPE_UP_RIGHT {
+                            // entered a skirt
+                            // look for skirt exit
+                            'skirt_tracer: loop {
+                                ray += 1;
+                                if ray >= map.cols {
+                                    break 'ray_tracer;
+                                }
+                                if let Some(next_pipe) = row[ray] {
+                                    if next_pipe == &PIPE_UP_LEFT || next_pipe == &PIPE_DOWN_LEFT {
+                                        // found skirt exit
+                                        // check if in same direction of diff directions
+                                        let pipe_ud =
+                                            if pipe == &PIPE_DOWN_RIGHT { &DOWN } else { &UP };
+
+                                        let next_pipe_ud = if next_pipe == &PIPE_DOWN_LEFT {
+                                            &DOWN
+                                        } else {
+                                            &UP
+                                        };
+
+                                        // is same direction, no change in wall count
+                                        if pipe_ud != next_pipe_ud {
+                                            wall_count += 1;
+                                        }
+                                        break 'skirt_tracer;
+                                    }
+                                }
+                            }
+                            ray += 1;
+                            continue;
+                        }
+                    }
+                    ray += 1;
+                }
+            }
+            *cell_count = wall_count;
+        }
+    }
+    wall_counts
+}
+
+pub fn count_inside_points(wall_counts: Vec<Vec<usize>>) -> usize {
+    let mut count = 0;
+    for row in wall_counts.iter() {
+        for element in row.iter() {
+            if *element % 2 == 1 {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Tiles enclosed by the loop through `start_point`.
+pub fn count_enclosed(map: Map, start_point: Point, method: Method) -> Result<usize, String> {
+    let path = traverse(&map, start_point)?;
+    match method {
+        Method::Area => Ok(enclosed_tiles(&path)),
+        Method::RayCast => {
+            // the ray tracer needs to know which pipe is under S
+            let map = fill_start(map, &start_point).map_err(|err| err.to_string())?;
+            Ok(count_inside_points(make_wall_counts(&map, &path)))
+        }
+    }
+}
+
+pub static BOX_DRAWING: [(&Pipe, char); 6] = [
+    (&PIPE_UP_LEFT, '┘'),
+    (&PIPE_UP_DOWN, '│'),
+    (&PIPE_UP_RIGHT, '└'),
+    (&PIPE_DOWN_RIGHT, '┌'),
+    (&PIPE_LEFT_RIGHT, '─'),
+    (&PIPE_DOWN_LEFT, '┐'),
+];
+
+pub fn box_symbol(pipe: &Pipe) -> char {
+    BOX_DRAWING
+        .iter()
+        .find(|(candidate, _)| *candidate == pipe)
+        .map(|(_, symbol)| *symbol)
+        .unwrap()
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Tile {
+    Loop,
+    Inside,
+    Outside,
+}
+
+/// Which tiles are on the loop, inside it or outside it. `map` needs the pipe
+/// under S filled in (see `fill_start`).
+///
+/// Walking along a row we cross the loop every time we pass a loop pipe that
+/// goes up, so tiles after an odd number of those are inside.
+pub fn classify(map: &Map, path: &[Point]) -> Vec<Vec<Tile>> {
+    let mut on_loop = vec![vec![false; map.cols]; map.rows];
+    for point in path {
+        on_loop[point.i][point.j] = true;
+    }
+    map.map
+        .iter()
+        .zip(on_loop.iter())
+        .map(|(row, row_on_loop)| {
+            let mut inside = false;
+            row.iter()
+                .zip(row_on_loop.iter())
+                .map(|(pipe, on_loop)| match (pipe, on_loop) {
+                    (Some(pipe), true) => {
+                        if pipe.0 == &UP {
+                            inside = !inside;
+                        }
+                        Tile::Loop
+                    }
+                    _ if inside => Tile::Inside,
+                    _ => Tile::Outside,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The map with S replaced by its pipe, and every tile classified.
+pub fn classify_map(map: Map, start_point: Point) -> Result<(Map, Vec<Vec<Tile>>), String> {
+    let path = traverse(&map, start_point)?;
+    let map = fill_start(map, &start_point).map_err(|err| err.to_string())?;
+    let tiles = classify(&map, &path);
+    Ok((map, tiles))
+}
+
+/// Draws the map with box-drawing characters. Without colour, tiles inside
+/// the loop are shaded ▓ and tiles outside ░. With colour the loop is bold
+/// yellow, the inside has a green background and the outside is dimmed.
+pub fn render_text(map: &Map, tiles: &[Vec<Tile>], colour: bool) -> String {
+    let mut output = String::new();
+    for (row, row_tiles) in map.map.iter().zip(tiles.iter()) {
+        for (pipe, tile) in row.iter().zip(row_tiles.iter()) {
+            let symbol = pipe.map(box_symbol).unwrap_or('·');
+            if !colour {
+                output.push(match tile {
+                    Tile::Loop => symbol,
+                    Tile::Inside => '▓',
+                    Tile::Outside => '░',
+                });
+                continue;
+            }
+            let style = match tile {
+                Tile::Loop => "\x1b[1;33m",
+                Tile::Inside => "\x1b[30;42m",
+                Tile::Outside => "\x1b[2m",
+            };
+            output.push_str(style);
+            output.push(symbol);
+            output.push_str("\x1b[0m");
+        }
+        output.push('\n');
+    }
+    output
+}
+
+const LOOP_COLOUR: [u8; 3] = [255, 200, 0];
+const PIPE_COLOUR: [u8; 3] = [90, 90, 90];
+const INSIDE_COLOUR: [u8; 3] = [30, 120, 50];
+const OUTSIDE_COLOUR: [u8; 3] = [20, 20, 30];
+
+/// The same picture as a binary PPM, `scale` pixels per tile. Pipes are drawn
+/// as lines through the middle of their tile.
+pub fn render_ppm(map: &Map, tiles: &[Vec<Tile>], scale: usize) -> Vec<u8> {
+    let scale = scale.max(3);
+    let (height, width) = (map.rows * scale, map.cols * scale);
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    let header = image.len();
+    image.resize(header + width * height * 3, 0);
+    // pixels this close to the middle of a tile are part of the pipe
+    let half_width = (scale / 6) as isize;
+    let middle = (scale / 2) as isize;
+    for (i, (row, row_tiles)) in map.map.iter().zip(tiles.iter()).enumerate() {
+        for (j, (pipe, tile)) in row.iter().zip(row_tiles.iter()).enumerate() {
+            let background = match tile {
+                Tile::Inside => INSIDE_COLOUR,
+                _ => OUTSIDE_COLOUR,
+            };
+            let foreground = match tile {
+                Tile::Loop => LOOP_COLOUR,
+                _ => PIPE_COLOUR,
+            };
+            for y in 0..scale {
+                for x in 0..scale {
+                    let (dy, dx) = (y as isize - middle, x as isize - middle);
+                    let on_pipe = pipe.is_some_and(|pipe| {
+                        [pipe.0, pipe.1].iter().any(|direction| {
+                            // the half of the centre line heading towards `direction`
+                            let along = dy * direction.del_i + dx * direction.del_j;
+                            let across = dy * direction.del_j - dx * direction.del_i;
+                            along >= -half_width && across.abs() <= half_width
+                        })
+                    });
+                    let colour = if on_pipe { foreground } else { background };
+                    let offset = header + ((i * scale + y) * width + j * scale + x) * 3;
+                    image[offset..offset + 3].copy_from_slice(&colour);
+                }
+            }
+        }
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_examples() -> Vec<(&'static str, usize)> {
+        vec![
+            (
+                "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........",
+                4,
+            ),
+            (
+                "..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........",
+                4,
+            ),
+            (
+                ".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...",
+                8,
+            ),
+            (
+                "FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L",
+                10,
+            ),
+            (
+                "7-F7-
+.FJ|7
+SJLL7
+|F--J
+LJ.LJ",
+                1,
+            ),
+            (
+                ".....
+.S-7.
+.|.|.
+.L-J.
+.....",
+                1,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_methods_agree() {
+        for (input, expected) in make_examples() {
+            let (map, start_point) = parse_input(input).unwrap();
+            let by_area = count_enclosed(map, start_point, Method::Area).unwrap();
+            let (map, start_point) = parse_input(input).unwrap();
+            let by_rays = count_enclosed(map, start_point, Method::RayCast).unwrap();
+            assert_eq!(by_area, expected, "{input}");
+            assert_eq!(by_rays, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_enclosed_tiles_direction() {
+        // walking the same loop the other way round gives the same count
+        let (map, start_point) = parse_input(make_examples()[2].0).unwrap();
+        let mut path = traverse(&map, start_point).unwrap();
+        assert_eq!(enclosed_tiles(&path), 8);
+        path.reverse();
+        assert_eq!(enclosed_tiles(&path), 8);
+    }
+
+    #[test]
+    fn test_broken_loop() {
+        let (map, start_point) = parse_input(
+            ".....
+.S-7.
+.|...
+.L-J.
+.....",
+        )
+        .unwrap();
+        assert!(traverse(&map, start_point).is_err());
+    }
+
+    #[test]
+    fn test_classify_matches_count() {
+        for (input, expected) in make_examples() {
+            let (map, start_point) = parse_input(input).unwrap();
+            let (_, tiles) = classify_map(map, start_point).unwrap();
+            let inside = tiles.iter().flatten().filter(|tile| **tile == Tile::Inside);
+            assert_eq!(inside.count(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_render_text() {
+        let (map, start_point) = parse_input(make_examples()[5].0).unwrap();
+        let (map, tiles) = classify_map(map, start_point).unwrap();
+        assert_eq!(
+            render_text(&map, &tiles, false),
+            "░░░░░\n░┌─┐░\n░│▓│░\n░└─┘░\n░░░░░\n"
+        );
+        let coloured = render_text(&map, &tiles, true);
+        assert!(coloured.contains("\x1b[1;33m┌\x1b[0m"));
+        assert!(coloured.contains("\x1b[30;42m·\x1b[0m"));
+    }
+
+    #[test]
+    fn test_render_ppm() {
+        let (map, start_point) = parse_input(make_examples()[5].0).unwrap();
+        let (map, tiles) = classify_map(map, start_point).unwrap();
+        let image = render_ppm(&map, &tiles, 6);
+        let header = b"P6\n30 30\n255\n";
+        assert!(image.starts_with(header));
+        assert_eq!(image.len(), header.len() + 30 * 30 * 3);
+        let pixel = |y: usize, x: usize| {
+            let offset = header.len() + (y * 30 + x) * 3;
+            image[offset..offset + 3].to_vec()
+        };
+        // middle of the S tile is on the loop, the middle of the map is inside
+        assert_eq!(pixel(9, 9), LOOP_COLOUR);
+        assert_eq!(pixel(15, 15), INSIDE_COLOUR);
+        // the top left corner of the ┌ at S has no pipe in it
+        assert_eq!(pixel(6, 6), OUTSIDE_COLOUR);
+    }
+
+    #[test]
+    fn test_start_errors() {
+        let (map, start_point) = parse_input(
+            ".....
+.S-7.
+.....",
+        )
+        .unwrap();
+        let err = infer_start(&map, &start_point).unwrap_err();
+        assert_eq!(
+            err,
+            StartError::TooFewConnections {
+                start: Point { i: 1, j: 1 },
+                connections: 1
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "start at (1, 1) connects to 1 pipes, it needs 2"
+        );
+
+        let (map, start_point) = parse_input(
+            ".....
+.S-7.
+.|...
+.L-J.
+.....",
+        )
+        .unwrap();
+        assert_eq!(
+            infer_start(&map, &start_point),
+            Err(StartError::NoClosedLoop {
+                start: Point { i: 1, j: 1 }
+            })
+        );
+
+        // S closes the top left loop as J or the bottom right one as F
+        let (map, start_point) = parse_input(
+            "F7.
+LS7
+.LJ",
+        )
+        .unwrap();
+        let err = infer_start(&map, &start_point).unwrap_err();
+        assert_eq!(
+            err,
+            StartError::Ambiguous {
+                start: Point { i: 1, j: 1 },
+                pipes: vec!['J', 'F']
+            }
+        );
+        assert_eq!(err.to_string(), "start at (1, 1) could be any of JF");
+        assert!(count_enclosed(map, start_point, Method::Area).is_err());
+    }
+
+    #[test]
+    fn test_infer_start() {
+        let (map, start_point) = parse_input(make_examples()[3].0).unwrap();
+        assert_eq!(infer_start(&map, &start_point), Ok(&PIPE_DOWN_LEFT));
+        let (map, start_point) = parse_input(make_examples()[4].0).unwrap();
+        assert_eq!(infer_start(&map, &start_point), Ok(&PIPE_DOWN_RIGHT));
+    }
+
+    #[test]
+    fn test_find_loops() {
+        let map = parse_map(
+            "F7.F-7
+LJ.|.|
+...L-J",
+        );
+        let loops = find_loops(&map);
+        let summary = loops
+            .iter()
+            .map(|pipe_loop| (pipe_loop.tiles[0], pipe_loop.length(), pipe_loop.enclosed()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![(Point { i: 0, j: 0 }, 4, 0), (Point { i: 0, j: 3 }, 8, 1)]
+        );
+
+        // the main loop is found once S is filled in, alongside any loops in the junk
+        let (map, start_point) = parse_input(make_examples()[3].0).unwrap();
+        let main_loop = traverse(&map, start_point).unwrap();
+        let map = fill_start(map, &start_point).unwrap();
+        let loops = find_loops(&map);
+        let found = loops
+            .iter()
+            .find(|pipe_loop| pipe_loop.tiles.contains(&start_point))
+            .unwrap();
+        assert_eq!(found.length(), main_loop.len());
+        assert_eq!(found.farthest(), main_loop.len() / 2);
+        assert_eq!(found.enclosed(), 10);
+    }
+
+    #[test]
+    fn test_start_count() {
+        assert_eq!(
+            parse_input("F7.\nLJ.\n...").err(),
+            Some(StartError::MissingStart)
+        );
+        let err = parse_input("S-7\n|.|\nL-S").err().unwrap();
+        assert_eq!(
+            err,
+            StartError::MultipleStarts {
+                starts: vec![Point { i: 0, j: 0 }, Point { i: 2, j: 2 }]
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "the map has 2 start tiles S, at (0, 0), (2, 2)"
+        );
+    }
+}
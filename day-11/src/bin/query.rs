@@ -0,0 +1,33 @@
+use day_11::{parse_input, Expansion, Galaxies};
+use std::env;
+use std::fs;
+
+// usage: query <filepath> <row factor> <column factor> (distance <a> <b> | farthest)
+// galaxies are numbered from 1, like the puzzle does
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let input = fs::read_to_string(&args[1]).expect("Error reading input file");
+    let factor = |k: usize| -> u64 { args[k].parse().expect("factors should be numbers") };
+    let map = parse_input(&input).unwrap_or_else(|err| panic!("{err}"));
+    let expansion = Expansion::factors(&map, factor(2), factor(3));
+    let galaxies = Galaxies::new(&map, &expansion).unwrap();
+    match args[4].as_str() {
+        "distance" => {
+            let galaxy = |k: usize| -> usize {
+                let number: usize = args[k].parse().expect("galaxies should be numbers");
+                number.wrapping_sub(1)
+            };
+            match galaxies.distance(galaxy(5), galaxy(6)) {
+                Some(distance) => println!("distance: {distance}"),
+                None => println!("there are only {} galaxies", galaxies.len()),
+            }
+        }
+        "farthest" => match galaxies.farthest_pair() {
+            Some((a, b, distance)) => {
+                println!("galaxies {} and {} are {} apart", a + 1, b + 1, distance)
+            }
+            None => println!("there are fewer than two galaxies"),
+        },
+        command => println!("unknown command {command:?}, use distance or farthest"),
+    }
+}
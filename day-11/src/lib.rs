@@ -0,0 +1,288 @@
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Point {
+    pub i: usize,
+    pub j: usize,
+}
+
+pub struct Map {
+    pub points: Vec<Point>,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+/// Every row needs to be as long as the first.
+pub fn parse_input(input: &str) -> Result<Map, String> {
+    let cols = match input.lines().next() {
+        Some(line) => line.chars().count(),
+        None => return Err("the map is empty".to_string()),
+    };
+    let mut points: Vec<Point> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let length = line.chars().count();
+        if length != cols {
+            return Err(format!(
+                "row {} has {length} columns but the first has {cols}",
+                i + 1
+            ));
+        }
+        for (j, c) in line.chars().enumerate() {
+            if c == '#' {
+                points.push(Point { i, j });
+            }
+        }
+    }
+    let rows = input.lines().count();
+    Ok(Map { points, rows, cols })
+}
+
+/// How tall every row and how wide every column is once the universe has
+/// expanded.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Expansion {
+    pub row_weights: Vec<u64>,
+    pub col_weights: Vec<u64>,
+}
+
+impl Expansion {
+    /// Every row without a galaxy becomes `row_factor` rows, and every such
+    /// column `col_factor` columns.
+    pub fn factors(map: &Map, row_factor: u64, col_factor: u64) -> Expansion {
+        let mut row_weights = vec![row_factor; map.rows];
+        let mut col_weights = vec![col_factor; map.cols];
+        for point in map.points.iter() {
+            row_weights[point.i] = 1;
+            col_weights[point.j] = 1;
+        }
+        Expansion {
+            row_weights,
+            col_weights,
+        }
+    }
+
+    pub fn uniform(map: &Map, factor: u64) -> Expansion {
+        Expansion::factors(map, factor, factor)
+    }
+}
+
+// where each row (or column) starts after expansion, None if the expanded
+// universe doesn't fit in a u64
+fn offsets(weights: &[u64]) -> Option<Vec<u64>> {
+    let mut offsets = Vec::with_capacity(weights.len());
+    let mut offset: u64 = 0;
+    for weight in weights {
+        offsets.push(offset);
+        offset = offset.checked_add(*weight)?;
+    }
+    Some(offsets)
+}
+
+// sum of |a - b| over all pairs
+fn sum_of_differences(mut values: Vec<u64>) -> u128 {
+    values.sort_unstable();
+    let mut total: u128 = 0;
+    let mut before: u128 = 0;
+    for (k, value) in values.iter().enumerate() {
+        // every earlier value is at most this one
+        total += *value as u128 * k as u128 - before;
+        before += *value as u128;
+    }
+    total
+}
+
+/// Galaxy positions after expansion, in the order they appear in the map.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Galaxies {
+    pub positions: Vec<(u64, u64)>,
+}
+
+impl Galaxies {
+    pub fn new(map: &Map, expansion: &Expansion) -> Result<Galaxies, String> {
+        if expansion.row_weights.len() != map.rows || expansion.col_weights.len() != map.cols {
+            return Err(format!(
+                "expansion is {}x{} but the map is {}x{}",
+                expansion.row_weights.len(),
+                expansion.col_weights.len(),
+                map.rows,
+                map.cols
+            ));
+        }
+        let overflow = || "the expanded universe is too big for a u64".to_string();
+        let row_offsets = offsets(&expansion.row_weights).ok_or_else(overflow)?;
+        let col_offsets = offsets(&expansion.col_weights).ok_or_else(overflow)?;
+        let positions = map
+            .points
+            .iter()
+            .map(|point| (row_offsets[point.i], col_offsets[point.j]))
+            .collect();
+        Ok(Galaxies { positions })
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Distance between galaxy `a` and galaxy `b`, counting from 0. `None`
+    /// if either is missing or the distance doesn't fit in a u64.
+    pub fn distance(&self, a: usize, b: usize) -> Option<u64> {
+        let (a, b) = (self.positions.get(a)?, self.positions.get(b)?);
+        a.0.abs_diff(b.0).checked_add(a.1.abs_diff(b.1))
+    }
+
+    /// Sum of the distances between every pair of galaxies. Rows and columns
+    /// add up separately, so this only needs them sorted.
+    pub fn total_distance(&self) -> u128 {
+        let rows = self.positions.iter().map(|position| position.0).collect();
+        let cols = self.positions.iter().map(|position| position.1).collect();
+        sum_of_differences(rows) + sum_of_differences(cols)
+    }
+
+    /// The two galaxies farthest apart, and how far that is.
+    ///
+    /// |di| + |dj| is the larger of |di + dj| and |di - dj|, so the farthest
+    /// pair is the extremes along one of the two diagonals.
+    pub fn farthest_pair(&self) -> Option<(usize, usize, u64)> {
+        if self.positions.len() < 2 {
+            return None;
+        }
+        let mut best: Option<(usize, usize, u64)> = None;
+        for sign in [1, -1] {
+            let along = |k: &usize| {
+                let (i, j) = self.positions[*k];
+                i as i128 + sign * j as i128
+            };
+            let low = (0..self.positions.len()).min_by_key(along)?;
+            let high = (0..self.positions.len()).max_by_key(along)?;
+            let distance = self.distance(low, high)?;
+            if best.is_none_or(|(_, _, farthest)| distance > farthest) {
+                best = Some((low.min(high), low.max(high), distance));
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_map() -> Map {
+        parse_input(
+            "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....",
+        )
+        .unwrap()
+    }
+
+    // the old quadratic sum, for checking against
+    fn brute_force(galaxies: &Galaxies) -> u128 {
+        let mut total = 0;
+        for a in 0..galaxies.len() {
+            for b in a + 1..galaxies.len() {
+                total += galaxies.distance(a, b).unwrap() as u128;
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn test_example_totals() {
+        let map = example_map();
+        for (factor, expected) in [(2, 374), (10, 1030), (100, 8410)] {
+            let galaxies = Galaxies::new(&map, &Expansion::uniform(&map, factor)).unwrap();
+            assert_eq!(galaxies.total_distance(), expected);
+            assert_eq!(brute_force(&galaxies), expected);
+        }
+    }
+
+    #[test]
+    fn test_pair_distances() {
+        // the puzzle numbers galaxies from 1
+        let map = example_map();
+        let galaxies = Galaxies::new(&map, &Expansion::uniform(&map, 2)).unwrap();
+        assert_eq!(galaxies.distance(4, 8), Some(9));
+        assert_eq!(galaxies.distance(0, 6), Some(15));
+        assert_eq!(galaxies.distance(2, 5), Some(17));
+        assert_eq!(galaxies.distance(7, 8), Some(5));
+        assert_eq!(galaxies.distance(0, 9), None);
+    }
+
+    #[test]
+    fn test_separate_factors() {
+        let map = example_map();
+        let expansion = Expansion::factors(&map, 1, 2);
+        // two empty rows stay as they are, three empty columns double
+        assert_eq!(expansion.row_weights.iter().sum::<u64>(), 10);
+        assert_eq!(expansion.col_weights.iter().sum::<u64>(), 13);
+        let galaxies = Galaxies::new(&map, &expansion).unwrap();
+        assert_eq!(galaxies.total_distance(), brute_force(&galaxies));
+        assert_eq!(galaxies.distance(4, 8), Some(8));
+    }
+
+    #[test]
+    fn test_row_weights() {
+        let map = example_map();
+        let mut expansion = Expansion::uniform(&map, 1);
+        // stretch the first row, which only changes distances to galaxy 1
+        expansion.row_weights[0] = 5;
+        let galaxies = Galaxies::new(&map, &expansion).unwrap();
+        assert_eq!(galaxies.distance(0, 1), Some(4 + 5));
+        assert_eq!(galaxies.distance(1, 2), Some(8));
+        assert_eq!(galaxies.total_distance(), brute_force(&galaxies));
+
+        expansion.row_weights.pop();
+        assert!(Galaxies::new(&map, &expansion).is_err());
+    }
+
+    #[test]
+    fn test_farthest_pair() {
+        let map = example_map();
+        let galaxies = Galaxies::new(&map, &Expansion::uniform(&map, 2)).unwrap();
+        let (a, b, distance) = galaxies.farthest_pair().unwrap();
+        let mut farthest = 0;
+        for x in 0..galaxies.len() {
+            for y in x + 1..galaxies.len() {
+                farthest = farthest.max(galaxies.distance(x, y).unwrap());
+            }
+        }
+        assert_eq!(distance, farthest);
+        assert_eq!(galaxies.distance(a, b), Some(farthest));
+
+        let map = parse_input("..#..").unwrap();
+        let galaxies = Galaxies::new(&map, &Expansion::uniform(&map, 2)).unwrap();
+        assert_eq!(galaxies.farthest_pair(), None);
+    }
+
+    #[test]
+    fn test_bad_maps() {
+        assert_eq!(
+            parse_input("#..\n..\n..#").err(),
+            Some("row 2 has 2 columns but the first has 3".to_string())
+        );
+        assert!(parse_input("").is_err());
+    }
+
+    #[test]
+    fn test_expansion_overflow() {
+        // three empty rows of u64::MAX / 2 each run past u64::MAX
+        let map = parse_input("...\n...\n...\n#..").unwrap();
+        let expansion = Expansion::factors(&map, u64::MAX / 2, 1);
+        assert!(Galaxies::new(&map, &expansion).is_err());
+        // two fit, but not the distance across both axes
+        let map = parse_input("#..\n...\n..#").unwrap();
+        let expansion = Expansion::uniform(&map, u64::MAX / 2);
+        let galaxies = Galaxies::new(&map, &expansion).unwrap();
+        assert_eq!(galaxies.distance(0, 1), None);
+    }
+}
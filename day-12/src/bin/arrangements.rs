@@ -0,0 +1,35 @@
+use day_12::{parse_line, Arrangements};
+use std::env;
+use std::fs;
+
+// usage: arrangements <filepath> <line number> [k]
+// prints every arrangement of the row, or only the k-th (from 0) when k is given
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let input = fs::read_to_string(&args[1]).expect("Error reading input file");
+    let line_number: usize = args[2].parse().expect("line number should be a number");
+    let line = input
+        .lines()
+        .nth(line_number.wrapping_sub(1))
+        .expect("no such line in the input");
+    let (_, (symbols, nums)) = parse_line(line).unwrap();
+    let Some(arrangements) = Arrangements::new(&symbols, &nums) else {
+        println!("{line}: too many arrangements to count");
+        return;
+    };
+    println!("{line}: {} arrangements", arrangements.count());
+    match args.get(3) {
+        Some(k) => {
+            let k: u128 = k.parse().expect("k should be a number");
+            match arrangements.nth(k) {
+                Some(arrangement) => println!("{k}: {arrangement}"),
+                None => println!("there is no arrangement {k}"),
+            }
+        }
+        None => {
+            for (k, arrangement) in arrangements.iter().enumerate() {
+                println!("{k}: {arrangement}");
+            }
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use day_12::{render_grid, Nonogram, Solution};
+use std::env;
+use std::fs;
+
+// usage: nonogram <filepath>
+// row clues, a blank line, then column clues, one comma separated line each
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let input = fs::read_to_string(&args[1]).expect("Error reading input file");
+    let nonogram = match Nonogram::parse(&input) {
+        Ok(nonogram) => nonogram,
+        Err(message) => {
+            println!("{message}");
+            return;
+        }
+    };
+    match nonogram.solve() {
+        Solution::None => println!("no solution"),
+        Solution::Unique(grid) => {
+            println!("unique solution:");
+            print!("{}", render_grid(&grid));
+        }
+        Solution::Multiple(first, second) => {
+            println!("more than one solution, for example:");
+            print!("{}", render_grid(&first));
+            println!();
+            print!("{}", render_grid(&second));
+        }
+    }
+}
@@ -0,0 +1,647 @@
+use nom::bytes::complete::tag;
+use nom::character::complete::{newline, one_of, u64};
+use nom::multi::many0;
+use nom::multi::separated_list0;
+use nom::sequence::separated_pair;
+use nom::IResult;
+use num_bigint::BigUint;
+use num_traits::{CheckedAdd, One, Zero};
+use rayon::prelude::*;
+use std::fmt;
+use std::str::FromStr;
+
+/// A row of springs and the sizes of its blocks of damaged springs.
+pub type Row = (Vec<char>, Vec<u64>);
+
+pub fn parse_line(input: &str) -> IResult<&str, Row> {
+    separated_pair(
+        many0(one_of(".?#")),
+        tag(" "),
+        separated_list0(tag(","), u64),
+    )(input)
+}
+
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Row>> {
+    separated_list0(newline, parse_line)(input)
+}
+
+/// Every way of filling in the `?`s of a row so the blocks of `#` match its
+/// numbers. Arrangements are numbered in the order the search tries them:
+/// at each `?` the ones with a `.` there come first.
+pub struct Arrangements {
+    symbols: Vec<char>,
+    nums: Vec<usize>,
+    // the counter's table, how many ways symbols[pos..] can hold nums[group..]
+    counter: Counter<u128>,
+}
+
+impl Arrangements {
+    /// `None` if the counts don't fit in a u128.
+    pub fn new(symbols: &[char], nums: &[u64]) -> Option<Arrangements> {
+        let mut counter = Counter::new();
+        counter.count(symbols, nums)?;
+        Some(Arrangements {
+            symbols: symbols.to_vec(),
+            nums: nums.iter().map(|num| *num as usize).collect(),
+            counter,
+        })
+    }
+
+    fn ways(&self, pos: usize, group: usize) -> u128 {
+        self.counter.ways[pos * (self.nums.len() + 1) + group]
+    }
+
+    // symbols[pos] can be a '.'
+    fn can_skip(&self, pos: usize) -> bool {
+        self.symbols[pos] != '#'
+    }
+
+    // Where we carry on from if block `group` starts at `pos`. The block needs
+    // room, no '.' inside it and no '#' straight after it.
+    fn place_block(&self, pos: usize, group: usize) -> Option<usize> {
+        let block_size = *self.nums.get(group)?;
+        let end = pos + block_size;
+        if end > self.symbols.len() || self.symbols[pos..end].contains(&'.') {
+            return None;
+        }
+        match self.symbols.get(end) {
+            None => Some(end),
+            Some('#') => None,
+            // skip over the '.' that ends the block
+            Some(_) => Some(end + 1),
+        }
+    }
+
+    pub fn count(&self) -> u128 {
+        self.ways(0, 0)
+    }
+
+    /// The `k`-th arrangement, counting from 0, without listing the ones
+    /// before it.
+    pub fn nth(&self, mut k: u128) -> Option<String> {
+        if k >= self.count() {
+            return None;
+        }
+        let mut arrangement = String::with_capacity(self.symbols.len());
+        let (mut pos, mut group) = (0, 0);
+        while pos < self.symbols.len() {
+            let skipping = if self.can_skip(pos) {
+                self.ways(pos + 1, group)
+            } else {
+                0
+            };
+            if k < skipping {
+                arrangement.push('.');
+                pos += 1;
+                continue;
+            }
+            // there are more than `skipping` arrangements left, so the block fits
+            k -= skipping;
+            let next = self.place_block(pos, group)?;
+            let block_size = self.nums[group];
+            arrangement.extend(std::iter::repeat_n('#', block_size));
+            if next > pos + block_size {
+                arrangement.push('.');
+            }
+            pos = next;
+            group += 1;
+        }
+        Some(arrangement)
+    }
+
+    /// Every arrangement in order, worked out one at a time as they're asked for.
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.count()).map_while(|k| self.nth(k))
+    }
+}
+
+/// Whether `arrangement` is one of the ways to fill in `symbols` for `nums`.
+pub fn is_arrangement(arrangement: &str, symbols: &[char], nums: &[u64]) -> bool {
+    if arrangement.chars().count() != symbols.len() {
+        return false;
+    }
+    let fits = arrangement
+        .chars()
+        .zip(symbols.iter())
+        .all(|(c, symbol)| *symbol == '?' && c != '?' || c == *symbol);
+    let blocks: Vec<u64> = arrangement
+        .split('.')
+        .filter(|block| !block.is_empty())
+        .map(|block| block.len() as u64)
+        .collect();
+    fits && blocks == nums
+}
+
+/// The row repeated `factor` times, joined by `?`, with its numbers repeated
+/// to match.
+pub fn unfold(row: &Row, factor: usize) -> Row {
+    let (symbols, nums) = row;
+    let mut unfolded_symbols = Vec::with_capacity((symbols.len() + 1) * factor);
+    for copy in 0..factor {
+        if copy > 0 {
+            unfolded_symbols.push('?');
+        }
+        unfolded_symbols.extend_from_slice(symbols);
+    }
+    (unfolded_symbols, nums.repeat(factor))
+}
+
+/// What arrangements can be counted in. Additions are checked so a type
+/// that's too small reports it instead of wrapping.
+pub trait Count: Clone + Zero + One + CheckedAdd + Send {}
+
+impl<T> Count for T where T: Clone + Zero + One + CheckedAdd + Send {}
+
+/// Counts arrangements bottom up over (position, group), keeping its table
+/// between rows so a whole input only allocates a few times.
+pub struct Counter<T> {
+    // ways[pos * (groups + 1) + group] is how many ways symbols[pos..] can hold nums[group..]
+    ways: Vec<T>,
+    // how many symbols from each position on could all be '#'
+    run: Vec<usize>,
+}
+
+impl<T: Count> Default for Counter<T> {
+    fn default() -> Self {
+        Counter::new()
+    }
+}
+
+impl<T: Count> Counter<T> {
+    pub fn new() -> Counter<T> {
+        Counter {
+            ways: Vec::new(),
+            run: Vec::new(),
+        }
+    }
+
+    /// Number of arrangements of the row, or `None` if it doesn't fit in `T`.
+    pub fn count(&mut self, symbols: &[char], nums: &[u64]) -> Option<T> {
+        let (len, groups) = (symbols.len(), nums.len());
+        let width = groups + 1;
+
+        self.run.clear();
+        self.run.resize(len + 1, 0);
+        for pos in (0..len).rev() {
+            if symbols[pos] != '.' {
+                self.run[pos] = self.run[pos + 1] + 1;
+            }
+        }
+
+        self.ways.clear();
+        self.ways.resize(width * (len + 1), T::zero());
+        // nothing left to fill, so only fine if there are no blocks left either
+        self.ways[len * width + groups] = T::one();
+        for pos in (0..len).rev() {
+            for group in 0..=groups {
+                let mut count = T::zero();
+                if symbols[pos] != '#' {
+                    count = self.ways[(pos + 1) * width + group].clone();
+                }
+                if let Some(block_size) = nums.get(group).map(|num| *num as usize) {
+                    let end = pos + block_size;
+                    // the block fits in a run of '#'/'?' and isn't followed by a '#'
+                    if self.run[pos] >= block_size && symbols.get(end) != Some(&'#') {
+                        let next = (end + 1).min(len);
+                        count = count.checked_add(&self.ways[next * width + group + 1])?;
+                    }
+                }
+                self.ways[pos * width + group] = count;
+            }
+        }
+        Some(self.ways[0].clone())
+    }
+}
+
+/// Total arrangements of every row unfolded `factor` times, counted in
+/// parallel. `None` if any count, or the total, doesn't fit in `T`.
+pub fn count_arrangements<T: Count>(rows: &[Row], factor: usize) -> Option<T> {
+    rows.par_iter()
+        .map_init(Counter::new, |counter, row| {
+            let (symbols, nums) = unfold(row, factor);
+            counter.count(&symbols, &nums)
+        })
+        .try_reduce(T::zero, |a, b| a.checked_add(&b))
+}
+
+/// Which integer type to count in.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Width {
+    U64,
+    U128,
+    Big,
+}
+
+impl FromStr for Width {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Width, String> {
+        match name {
+            "u64" => Ok(Width::U64),
+            "u128" => Ok(Width::U128),
+            "big" => Ok(Width::Big),
+            _ => Err(format!("unknown count type {name:?}, use u64, u128 or big")),
+        }
+    }
+}
+
+impl fmt::Display for Width {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Width::U64 => write!(f, "u64"),
+            Width::U128 => write!(f, "u128"),
+            Width::Big => write!(f, "big"),
+        }
+    }
+}
+
+/// `count_arrangements` in the chosen integer type, as a string.
+pub fn count_with(rows: &[Row], factor: usize, width: Width) -> Result<String, String> {
+    let total = match width {
+        Width::U64 => count_arrangements::<u64>(rows, factor).map(|total| total.to_string()),
+        Width::U128 => count_arrangements::<u128>(rows, factor).map(|total| total.to_string()),
+        Width::Big => count_arrangements::<BigUint>(rows, factor).map(|total| total.to_string()),
+    };
+    total.ok_or_else(|| format!("the count overflowed {}, try a wider type", width))
+}
+
+/// A picture puzzle built from the same clues as a row of springs: every row
+/// and every column lists its blocks of filled (`#`) cells.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Nonogram {
+    pub rows: Vec<Vec<u64>>,
+    pub cols: Vec<Vec<u64>>,
+}
+
+pub type Grid = Vec<Vec<char>>;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Solution {
+    None,
+    Unique(Grid),
+    // two of the solutions, there may be more
+    Multiple(Grid, Grid),
+}
+
+// a single 0 is how an empty line is written
+fn parse_clues(line: &str) -> Result<Vec<u64>, String> {
+    line.split(',')
+        .map(|num| num.trim().parse::<u64>())
+        .filter(|num| *num != Ok(0))
+        .collect::<Result<Vec<u64>, _>>()
+        .map_err(|_| format!("could not read clues {line:?}"))
+}
+
+impl Nonogram {
+    /// Row clues, a blank line, then column clues. One line per row or
+    /// column, with block sizes separated by commas and `0` for an empty line.
+    pub fn parse(input: &str) -> Result<Nonogram, String> {
+        let (rows, cols) = input
+            .trim()
+            .split_once("\n\n")
+            .ok_or("expected row clues and column clues separated by a blank line")?;
+        let rows = rows.lines().map(parse_clues).collect::<Result<_, _>>()?;
+        let cols = cols.lines().map(parse_clues).collect::<Result<_, _>>()?;
+        Ok(Nonogram { rows, cols })
+    }
+
+    /// Fills in the grid, looking for a second solution to tell whether the
+    /// first is unique.
+    pub fn solve(&self) -> Solution {
+        let mut counter = Counter::new();
+        let grid = vec![vec!['?'; self.cols.len()]; self.rows.len()];
+        let mut solutions = Vec::new();
+        self.search(grid, &mut counter, &mut solutions);
+        let mut solutions = solutions.into_iter();
+        match (solutions.next(), solutions.next()) {
+            (None, _) => Solution::None,
+            (Some(grid), None) => Solution::Unique(grid),
+            (Some(first), Some(second)) => Solution::Multiple(first, second),
+        }
+    }
+
+    // Propagate what the lines force, then guess the first unknown cell both
+    // ways. Stops once two solutions are found.
+    fn search(&self, mut grid: Grid, counter: &mut Counter<u128>, solutions: &mut Vec<Grid>) {
+        if solutions.len() >= 2 || self.propagate(&mut grid, counter).is_none() {
+            return;
+        }
+        let unknown = grid
+            .iter()
+            .enumerate()
+            .find_map(|(i, row)| row.iter().position(|cell| *cell == '?').map(|j| (i, j)));
+        match unknown {
+            None => solutions.push(grid),
+            Some((i, j)) => {
+                for guess in ['#', '.'] {
+                    let mut guessed = grid.clone();
+                    guessed[i][j] = guess;
+                    self.search(guessed, counter, solutions);
+                }
+            }
+        }
+    }
+
+    // Solves rows and columns on their own until nothing changes. `None` if a
+    // line can't be filled in at all.
+    fn propagate(&self, grid: &mut Grid, counter: &mut Counter<u128>) -> Option<()> {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (row, nums) in grid.iter_mut().zip(self.rows.iter()) {
+                changed |= solve_line(row, nums, counter)?;
+            }
+            for (j, nums) in self.cols.iter().enumerate() {
+                let mut col: Vec<char> = grid.iter().map(|row| row[j]).collect();
+                if solve_line(&mut col, nums, counter)? {
+                    changed = true;
+                    for (row, cell) in grid.iter_mut().zip(col) {
+                        row[j] = cell;
+                    }
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+// whether the line can still be filled in, an overflowing count has plenty of ways
+fn possible(line: &[char], nums: &[u64], counter: &mut Counter<u128>) -> bool {
+    counter.count(line, nums).is_none_or(|count| count > 0)
+}
+
+// Fixes every '?' that only one choice works for. Whether anything changed,
+// or `None` if the line has no arrangements.
+fn solve_line(line: &mut [char], nums: &[u64], counter: &mut Counter<u128>) -> Option<bool> {
+    if !possible(line, nums, counter) {
+        return None;
+    }
+    let mut changed = false;
+    for i in 0..line.len() {
+        if line[i] != '?' {
+            continue;
+        }
+        line[i] = '#';
+        let can_fill = possible(line, nums, counter);
+        line[i] = '.';
+        let can_empty = possible(line, nums, counter);
+        line[i] = match (can_fill, can_empty) {
+            (true, false) => '#',
+            (false, true) => '.',
+            _ => '?',
+        };
+        changed |= line[i] != '?';
+    }
+    Some(changed)
+}
+
+pub fn render_grid(grid: &Grid) -> String {
+    let mut output = String::new();
+    for row in grid {
+        output.extend(row.iter().map(|cell| match cell {
+            '#' => '█',
+            '.' => '·',
+            _ => '?',
+        }));
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_rows() -> Vec<Row> {
+        let input = "???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+        parse_input(input).unwrap().1
+    }
+
+    #[test]
+    fn test_counts() {
+        let counts: Vec<u128> = example_rows()
+            .iter()
+            .map(|(symbols, nums)| Arrangements::new(symbols, nums).unwrap().count())
+            .collect();
+        assert_eq!(counts, vec![1, 4, 1, 1, 4, 10]);
+    }
+
+    #[test]
+    fn test_listed_arrangements() {
+        let (symbols, nums) = &example_rows()[5];
+        let arrangements = Arrangements::new(symbols, nums).unwrap();
+        let listed: Vec<String> = arrangements.iter().collect();
+        assert_eq!(
+            listed,
+            vec![
+                ".###....##.#",
+                ".###...##..#",
+                ".###...##.#.",
+                ".###..##...#",
+                ".###..##..#.",
+                ".###..##.#..",
+                ".###.##....#",
+                ".###.##...#.",
+                ".###.##..#..",
+                ".###.##.#...",
+            ]
+        );
+        assert_eq!(arrangements.nth(3), Some(".###..##...#".to_string()));
+        assert_eq!(arrangements.nth(10), None);
+    }
+
+    #[test]
+    fn test_every_arrangement_is_valid() {
+        for (symbols, nums) in example_rows() {
+            let arrangements = Arrangements::new(&symbols, &nums).unwrap();
+            let listed: Vec<String> = arrangements.iter().collect();
+            assert_eq!(listed.len() as u128, arrangements.count());
+            assert!(listed
+                .iter()
+                .all(|arrangement| is_arrangement(arrangement, &symbols, &nums)));
+            let mut unique = listed.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(unique.len(), listed.len());
+        }
+    }
+
+    #[test]
+    fn test_sample_large_row() {
+        // far too many to list, but any one of them can be picked out
+        let symbols: Vec<char> = "?".repeat(100).chars().collect();
+        let arrangements = Arrangements::new(&symbols, &[1, 2, 3, 4, 5, 6]).unwrap();
+        let count = arrangements.count();
+        assert_eq!(count, 300500200);
+        for k in [0, 1, count / 2, count - 1] {
+            let arrangement = arrangements.nth(k).unwrap();
+            assert!(is_arrangement(&arrangement, &symbols, &[1, 2, 3, 4, 5, 6]));
+        }
+        assert_eq!(arrangements.nth(count), None);
+    }
+
+    #[test]
+    fn test_counts_past_u64() {
+        // forty 1s in 140 cells: C(101, 40) ways, more than a u64 holds
+        let symbols: Vec<char> = "?".repeat(140).chars().collect();
+        let nums = vec![1; 40];
+        let arrangements = Arrangements::new(&symbols, &nums).unwrap();
+        let count = arrangements.count();
+        assert_eq!(count, 22760158175837441993901710520);
+        let last = arrangements.nth(count - 1).unwrap();
+        assert!(is_arrangement(&last, &symbols, &nums));
+        // the first puts every spare '.' at the front, the last at the back
+        assert!(last.ends_with(&".".repeat(61)));
+        assert!(arrangements.nth(0).unwrap().starts_with(&".".repeat(61)));
+
+        // C(201, 100) doesn't fit in a u128 either
+        let symbols: Vec<char> = "?".repeat(300).chars().collect();
+        assert!(Arrangements::new(&symbols, &[1; 100]).is_none());
+    }
+
+    #[test]
+    fn test_no_arrangements() {
+        let arrangements = Arrangements::new(&['#', '.', '#'], &[3]).unwrap();
+        assert_eq!(arrangements.count(), 0);
+        assert_eq!(arrangements.iter().next(), None);
+    }
+
+    #[test]
+    fn test_counter_matches_arrangements() {
+        let mut counter = Counter::<u64>::new();
+        for (symbols, nums) in example_rows() {
+            let expected = Arrangements::new(&symbols, &nums).unwrap().count() as u64;
+            assert_eq!(counter.count(&symbols, &nums), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_unfold() {
+        let row = parse_line(".# 1").unwrap().1;
+        let (symbols, nums) = unfold(&row, 5);
+        assert_eq!(symbols.iter().collect::<String>(), ".#?.#?.#?.#?.#");
+        assert_eq!(nums, vec![1, 1, 1, 1, 1]);
+        assert_eq!(unfold(&row, 1), row);
+    }
+
+    #[test]
+    fn test_examples_unfolded() {
+        let rows = example_rows();
+        assert_eq!(count_arrangements::<u64>(&rows, 1), Some(21));
+        assert_eq!(count_arrangements::<u64>(&rows, 5), Some(525152));
+        let per_row: Vec<u64> = rows
+            .iter()
+            .map(|row| count_arrangements::<u64>(std::slice::from_ref(row), 5).unwrap())
+            .collect();
+        assert_eq!(per_row, vec![1, 16384, 1, 16, 2500, 506250]);
+    }
+
+    #[test]
+    fn test_wide_counts() {
+        // ?###???????? 3,2,1 has 10 * 15^(factor - 1) arrangements unfolded
+        let rows = vec![example_rows()[5].clone()];
+        let factor = 20;
+        let expected = BigUint::from(10u32) * BigUint::from(15u32).pow(factor as u32 - 1);
+        assert_eq!(count_arrangements::<u64>(&rows, factor), None);
+        assert_eq!(
+            count_arrangements::<u128>(&rows, factor),
+            Some(expected.to_string().parse::<u128>().unwrap())
+        );
+        let factor = 40;
+        let expected = BigUint::from(10u32) * BigUint::from(15u32).pow(factor as u32 - 1);
+        assert_eq!(
+            count_arrangements::<BigUint>(&rows, factor),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            count_with(&rows, factor, Width::Big),
+            Ok(expected.to_string())
+        );
+        assert!(count_with(&rows, factor, Width::U128).is_err());
+    }
+
+    fn grid(picture: &str) -> Grid {
+        picture.lines().map(|line| line.chars().collect()).collect()
+    }
+
+    #[test]
+    fn test_unique_nonogram() {
+        // a heart
+        let nonogram = Nonogram::parse(
+            "1,1
+5
+5
+3
+1
+
+2
+4
+4
+4
+2",
+        )
+        .unwrap();
+        let expected = grid(
+            ".#.#.
+#####
+#####
+.###.
+..#..",
+        );
+        assert_eq!(nonogram.solve(), Solution::Unique(expected.clone()));
+        assert_eq!(
+            render_grid(&expected),
+            "·█·█·\n█████\n█████\n·███·\n··█··\n"
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_nonogram() {
+        // either diagonal
+        let nonogram = Nonogram::parse("1\n1\n\n1\n1").unwrap();
+        assert_eq!(
+            nonogram.solve(),
+            Solution::Multiple(grid("#.\n.#"), grid(".#\n#."))
+        );
+    }
+
+    #[test]
+    fn test_backtracking_nonogram() {
+        // the lines on their own get stuck, only one guess then works out
+        let nonogram = Nonogram::parse(
+            "1
+2
+1
+1,1
+2,1
+
+1
+1,1
+1
+3
+1,1",
+        )
+        .unwrap();
+        let expected = grid(
+            ".#...
+...##
+...#.
+#..#.
+.##.#",
+        );
+        assert_eq!(nonogram.solve(), Solution::Unique(expected));
+    }
+
+    #[test]
+    fn test_impossible_nonogram() {
+        let nonogram = Nonogram::parse("2\n0\n\n1\n0").unwrap();
+        assert_eq!(nonogram.solve(), Solution::None);
+        assert!(Nonogram::parse("1\n1").is_err());
+        assert!(Nonogram::parse("1,x\n\n1").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day_13::{find_reflections, PackedLines};
+use std::fs;
+
+// the pre-series part 1 and part 2 searches, copied from the original bins,
+// so there is a baseline to compare the current layouts against
+mod original {
+    pub fn find_reflection(lines: &[Vec<bool>]) -> usize {
+        let num_lines = lines.len();
+        for i in 1..num_lines {
+            let window_length = i.min(num_lines - i);
+            let h1 = &lines[(i - window_length)..i]
+                .iter()
+                .rev()
+                .collect::<Vec<&Vec<bool>>>();
+
+            let h2 = &lines[i..i + window_length]
+                .iter()
+                .collect::<Vec<&Vec<bool>>>();
+
+            if h1 == h2 {
+                return i;
+            }
+        }
+        0
+    }
+
+    pub fn find_smudge(lines: &[Vec<bool>]) -> usize {
+        let num_lines = lines.len();
+        for i in 1..num_lines {
+            let window_length = i.min(num_lines - i);
+            let h1 = &lines[(i - window_length)..i]
+                .iter()
+                .rev()
+                .collect::<Vec<&Vec<bool>>>();
+
+            let h2 = &lines[i..i + window_length]
+                .iter()
+                .collect::<Vec<&Vec<bool>>>();
+
+            let diff = h1
+                .iter()
+                .zip(h2.iter())
+                .flat_map(|(h1_line, h2_line)| {
+                    h1_line
+                        .iter()
+                        .zip(h2_line.iter())
+                        .map(|(h1_char, h2_char)| if h1_char != h2_char { 1 } else { 0 })
+                })
+                .sum::<usize>();
+            if diff == 1 {
+                return i;
+            }
+        }
+        0
+    }
+
+    pub fn search(lines: &[Vec<bool>], max_smudges: usize) -> usize {
+        match max_smudges {
+            0 => find_reflection(lines),
+            _ => find_smudge(lines),
+        }
+    }
+}
+
+// rows of every pattern in the puzzle input, as bools
+fn input_patterns() -> Vec<Vec<Vec<bool>>> {
+    let input = fs::read_to_string("data/input1.txt").expect("Error reading input file");
+    input
+        .trim_end()
+        .split("\n\n")
+        .map(|pattern| {
+            pattern
+                .lines()
+                .map(|line| line.chars().map(|c| c == '#').collect())
+                .collect()
+        })
+        .collect()
+}
+
+// a pattern much wider than a word, mirrored around its middle row
+fn wide_pattern(rows: usize, cols: usize) -> Vec<Vec<bool>> {
+    let half: Vec<Vec<bool>> = (0..rows / 2)
+        .map(|i| (0..cols).map(|j| (i * 7 + j * 13) % 5 < 2).collect())
+        .collect();
+    half.iter().chain(half.iter().rev()).cloned().collect()
+}
+
+fn bench_reflections(c: &mut Criterion) {
+    let patterns = input_patterns();
+    let packed: Vec<PackedLines> = patterns
+        .iter()
+        .map(|rows| PackedLines::pack(rows))
+        .collect();
+    let mut group = c.benchmark_group("input");
+    for max_smudges in [0, 1] {
+        group.bench_function(format!("original, {max_smudges} smudges"), |b| {
+            b.iter(|| {
+                for rows in patterns.iter() {
+                    black_box(original::search(rows, max_smudges));
+                }
+            })
+        });
+        group.bench_function(format!("unpacked, {max_smudges} smudges"), |b| {
+            b.iter(|| {
+                for rows in patterns.iter() {
+                    black_box(find_reflections(rows.as_slice(), max_smudges));
+                }
+            })
+        });
+        group.bench_function(format!("packed, {max_smudges} smudges"), |b| {
+            b.iter(|| {
+                for rows in packed.iter() {
+                    black_box(find_reflections(rows, max_smudges));
+                }
+            })
+        });
+    }
+    group.finish();
+
+    let wide = wide_pattern(200, 1000);
+    let wide_packed = PackedLines::pack(&wide);
+    let mut group = c.benchmark_group("200x1000");
+    group.bench_function("original", |b| {
+        b.iter(|| black_box(original::search(&wide, 1)))
+    });
+    group.bench_function("unpacked", |b| {
+        b.iter(|| black_box(find_reflections(wide.as_slice(), 1)))
+    });
+    group.bench_function("packed", |b| {
+        b.iter(|| black_box(find_reflections(&wide_packed, 1)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_reflections);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+use day_13::{parse_input, Axis};
+use std::env;
+use std::fs;
+
+// usage: mirrors <filepath> [max smudges]
+// lists every mirror of every pattern and the cells that would have to change
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let contents = fs::read_to_string(&args[1]).expect("Something went wrong reading the file");
+    let max_smudges = args
+        .get(2)
+        .map(|max| {
+            max.parse::<usize>()
+                .expect("max smudges should be a number")
+        })
+        .unwrap_or(1);
+    for (i, pattern) in parse_input(&contents).iter().enumerate() {
+        let reflections = pattern.reflections(max_smudges);
+        if reflections.is_empty() {
+            println!("pattern {}: no mirrors", i + 1);
+        }
+        for reflection in reflections {
+            let (axis, before) = match reflection.axis {
+                Axis::Horizontal => ("horizontal", "row"),
+                Axis::Vertical => ("vertical", "column"),
+            };
+            print!(
+                "pattern {}: {} mirror after {} {}",
+                i + 1,
+                axis,
+                before,
+                reflection.position
+            );
+            if reflection.differences.is_empty() {
+                println!(", exact");
+            } else {
+                println!(", {} smudges:", reflection.smudges());
+            }
+            for [(a_row, a_col), (b_row, b_col)] in reflection.differences.iter() {
+                println!("  ({a_row}, {a_col}) <-> ({b_row}, {b_col})");
+            }
+        }
+    }
+}
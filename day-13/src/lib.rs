@@ -0,0 +1,419 @@
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Axis {
+    // a line between two rows
+    Horizontal,
+    // a line between two columns
+    Vertical,
+}
+
+/// A mirror between line `position - 1` and line `position`, and every pair
+/// of cells it reflects onto each other that don't match, as (row, column).
+#[derive(Debug, PartialEq, Clone)]
+pub struct Reflection {
+    pub axis: Axis,
+    pub position: usize,
+    pub differences: Vec<[(usize, usize); 2]>,
+}
+
+impl Reflection {
+    pub fn smudges(&self) -> usize {
+        self.differences.len()
+    }
+
+    pub fn score(&self) -> usize {
+        match self.axis {
+            Axis::Horizontal => 100 * self.position,
+            Axis::Vertical => self.position,
+        }
+    }
+}
+
+/// Something made of equally long lines of cells that can be compared.
+pub trait Lines {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How many cells differ between line `a` and line `b`.
+    fn distance(&self, a: usize, b: usize) -> usize;
+
+    /// The indices where line `a` and line `b` differ.
+    fn differences(&self, a: usize, b: usize) -> Vec<usize>;
+}
+
+impl Lines for [Vec<bool>] {
+    fn len(&self) -> usize {
+        <[Vec<bool>]>::len(self)
+    }
+
+    fn distance(&self, a: usize, b: usize) -> usize {
+        self[a]
+            .iter()
+            .zip(self[b].iter())
+            .filter(|(a_cell, b_cell)| a_cell != b_cell)
+            .count()
+    }
+
+    fn differences(&self, a: usize, b: usize) -> Vec<usize> {
+        self[a]
+            .iter()
+            .zip(self[b].iter())
+            .enumerate()
+            .filter(|(_, (a_cell, b_cell))| a_cell != b_cell)
+            .map(|(j, _)| j)
+            .collect()
+    }
+}
+
+/// Lines packed into bitmasks, `words` u64s per line so they can be any
+/// width. Comparing two lines is an XOR and a popcount per word.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PackedLines {
+    pub width: usize,
+    words: usize,
+    data: Vec<u64>,
+}
+
+impl PackedLines {
+    pub fn pack(lines: &[Vec<bool>]) -> PackedLines {
+        let width = lines.first().map_or(0, |line| line.len());
+        let words = width.div_ceil(64).max(1);
+        let mut data = vec![0; words * lines.len()];
+        for (i, line) in lines.iter().enumerate() {
+            for (j, cell) in line.iter().enumerate() {
+                if *cell {
+                    data[i * words + j / 64] |= 1 << (j % 64);
+                }
+            }
+        }
+        PackedLines { width, words, data }
+    }
+
+    pub fn line(&self, i: usize) -> &[u64] {
+        &self.data[i * self.words..(i + 1) * self.words]
+    }
+}
+
+impl Lines for PackedLines {
+    fn len(&self) -> usize {
+        self.data.len() / self.words
+    }
+
+    fn distance(&self, a: usize, b: usize) -> usize {
+        self.line(a)
+            .iter()
+            .zip(self.line(b).iter())
+            .map(|(a_word, b_word)| (a_word ^ b_word).count_ones() as usize)
+            .sum()
+    }
+
+    fn differences(&self, a: usize, b: usize) -> Vec<usize> {
+        let mut differences = Vec::new();
+        for (w, (a_word, b_word)) in self.line(a).iter().zip(self.line(b).iter()).enumerate() {
+            let mut diff = a_word ^ b_word;
+            while diff != 0 {
+                differences.push(w * 64 + diff.trailing_zeros() as usize);
+                // drop the lowest set bit
+                diff &= diff - 1;
+            }
+        }
+        differences
+    }
+}
+
+/// Every mirror between two of `lines` that's off by at most `max_smudges`
+/// cells. `lines` are taken as rows, so the mirrors are horizontal.
+pub fn find_reflections<L: Lines + ?Sized>(lines: &L, max_smudges: usize) -> Vec<Reflection> {
+    let num_lines = lines.len();
+    let mut reflections = Vec::new();
+    'axis: for i in 1..num_lines {
+        let window_length = i.min(num_lines - i);
+        let mut smudges = 0;
+        for offset in 0..window_length {
+            smudges += lines.distance(i - 1 - offset, i + offset);
+            if smudges > max_smudges {
+                continue 'axis;
+            }
+        }
+        // only worth finding where the differences are for the mirrors we keep
+        let mut differences = Vec::with_capacity(smudges);
+        if smudges > 0 {
+            for offset in 0..window_length {
+                let (a, b) = (i - 1 - offset, i + offset);
+                differences.extend(
+                    lines
+                        .differences(a, b)
+                        .into_iter()
+                        .map(|j| [(a, j), (b, j)]),
+                );
+            }
+        }
+        reflections.push(Reflection {
+            axis: Axis::Horizontal,
+            position: i,
+            differences,
+        });
+    }
+    reflections
+}
+
+pub struct Pattern {
+    pub rows: PackedLines,
+    pub cols: PackedLines,
+}
+
+impl Pattern {
+    pub fn new(data: Vec<Vec<bool>>) -> Self {
+        // make cols
+        let col_length = data[0].len();
+        let cols: Vec<Vec<bool>> = (0..col_length)
+            .map(|col| data.iter().map(|row| row[col]).collect::<Vec<bool>>())
+            .collect();
+
+        Pattern {
+            rows: PackedLines::pack(&data),
+            cols: PackedLines::pack(&cols),
+        }
+    }
+
+    /// Horizontal then vertical mirrors that are off by at most `max_smudges`.
+    pub fn reflections(&self, max_smudges: usize) -> Vec<Reflection> {
+        let mut reflections = find_reflections(&self.rows, max_smudges);
+        // columns are rows of the transposed pattern, so swap back
+        reflections.extend(find_reflections(&self.cols, max_smudges).into_iter().map(
+            |reflection| {
+                Reflection {
+                    axis: Axis::Vertical,
+                    position: reflection.position,
+                    differences: reflection
+                        .differences
+                        .iter()
+                        .map(|[(a_col, a_row), (b_col, b_row)]| {
+                            [(*a_row, *a_col), (*b_row, *b_col)]
+                        })
+                        .collect(),
+                }
+            },
+        ));
+        reflections
+    }
+
+    /// Summary of the mirror that is off by exactly `smudges` cells. There
+    /// should be exactly one, so finding none or several is an error.
+    pub fn score(&self, smudges: usize) -> Result<usize, String> {
+        let mirrors: Vec<Reflection> = self
+            .reflections(smudges)
+            .into_iter()
+            .filter(|reflection| reflection.smudges() == smudges)
+            .collect();
+        match &mirrors[..] {
+            [mirror] => Ok(mirror.score()),
+            [] => Err(format!("no mirror with {smudges} smudges")),
+            _ => Err(format!(
+                "{} mirrors with {smudges} smudges: {}",
+                mirrors.len(),
+                mirrors
+                    .iter()
+                    .map(|mirror| match mirror.axis {
+                        Axis::Horizontal => format!("after row {}", mirror.position),
+                        Axis::Vertical => format!("after column {}", mirror.position),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+}
+
+pub fn parse_pattern(input: &str) -> Pattern {
+    let mut data: Vec<Vec<bool>> = Vec::new();
+    for line in input.lines() {
+        let line_chars: Vec<bool> = line.chars().map(|c| c == '#').collect();
+        data.push(line_chars);
+    }
+
+    Pattern::new(data)
+}
+
+pub fn parse_input(input: &str) -> Vec<Pattern> {
+    let pattern_strings = input.trim_end().split("\n\n").collect::<Vec<&str>>();
+    pattern_strings.iter().map(|s| parse_pattern(s)).collect()
+}
+
+/// Total score of every pattern, using the mirrors off by exactly `smudges`.
+/// Errors name the pattern (from 1) that has no such mirror, or more than one.
+pub fn summarise(patterns: &[Pattern], smudges: usize) -> Result<usize, String> {
+    let mut total = 0;
+    for (i, pattern) in patterns.iter().enumerate() {
+        total += pattern
+            .score(smudges)
+            .map_err(|err| format!("pattern {}: {err}", i + 1))?;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_patterns() -> Vec<Pattern> {
+        parse_input(
+            "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#",
+        )
+    }
+
+    #[test]
+    fn test_exact_mirrors() {
+        let patterns = example_patterns();
+        let mirrors = patterns[0].reflections(0);
+        assert_eq!(
+            mirrors,
+            vec![Reflection {
+                axis: Axis::Vertical,
+                position: 5,
+                differences: vec![]
+            }]
+        );
+        assert_eq!(patterns[1].score(0), Ok(400));
+        assert_eq!(summarise(&patterns, 0), Ok(405));
+    }
+
+    #[test]
+    fn test_smudges() {
+        let patterns = example_patterns();
+        let smudged: Vec<Reflection> = patterns[0]
+            .reflections(1)
+            .into_iter()
+            .filter(|reflection| reflection.smudges() == 1)
+            .collect();
+        // the smudge is the top left cell, or the one it reflects onto
+        assert_eq!(
+            smudged,
+            vec![Reflection {
+                axis: Axis::Horizontal,
+                position: 3,
+                differences: vec![[(0, 0), (5, 0)]]
+            }]
+        );
+        let smudged = patterns[1]
+            .reflections(1)
+            .into_iter()
+            .find(|reflection| reflection.smudges() == 1)
+            .unwrap();
+        assert_eq!(smudged.differences, vec![[(0, 4), (1, 4)]]);
+        assert_eq!(summarise(&patterns, 1), Ok(400));
+    }
+
+    #[test]
+    fn test_tolerance_keeps_exact_mirrors() {
+        // with room for a smudge the exact mirror is still reported
+        let patterns = example_patterns();
+        let mirrors = patterns[1].reflections(1);
+        let positions: Vec<(Axis, usize, usize)> = mirrors
+            .iter()
+            .map(|reflection| (reflection.axis, reflection.position, reflection.smudges()))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![(Axis::Horizontal, 1, 1), (Axis::Horizontal, 4, 0)]
+        );
+    }
+
+    #[test]
+    fn test_multiple_and_missing_mirrors() {
+        // symmetric both ways
+        let pattern = parse_pattern("#..#\n.##.\n.##.\n#..#");
+        let mirrors = pattern.reflections(0);
+        assert_eq!(mirrors.len(), 2);
+        assert_eq!(
+            pattern.score(0),
+            Err("2 mirrors with 0 smudges: after row 2, after column 2".to_string())
+        );
+        assert_eq!(
+            summarise(
+                &example_patterns()
+                    .into_iter()
+                    .chain([pattern])
+                    .collect::<Vec<_>>(),
+                0
+            ),
+            Err("pattern 3: 2 mirrors with 0 smudges: after row 2, after column 2".to_string())
+        );
+
+        let pattern = parse_pattern("#.\n..");
+        assert_eq!(
+            pattern.score(0),
+            Err("no mirror with 0 smudges".to_string())
+        );
+        assert_eq!(
+            summarise(&[pattern], 0),
+            Err("pattern 1: no mirror with 0 smudges".to_string())
+        );
+    }
+
+    #[test]
+    fn test_packed_matches_unpacked() {
+        let input = "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.";
+        let lines: Vec<Vec<bool>> = input
+            .lines()
+            .map(|line| line.chars().map(|c| c == '#').collect())
+            .collect();
+        let packed = PackedLines::pack(&lines);
+        assert_eq!(packed.len(), 7);
+        for max_smudges in 0..4 {
+            assert_eq!(
+                find_reflections(&packed, max_smudges),
+                find_reflections(lines.as_slice(), max_smudges)
+            );
+        }
+    }
+
+    #[test]
+    fn test_wide_patterns() {
+        // 150 columns take three words a line
+        let mut lines = vec![vec![false; 150]; 4];
+        for line in lines.iter_mut() {
+            line[3] = true;
+            line[70] = true;
+            line[149] = true;
+        }
+        // a smudge past the first word
+        lines[0][100] = true;
+        let packed = PackedLines::pack(&lines);
+        assert_eq!(packed.line(0).len(), 3);
+        assert_eq!(packed.distance(0, 3), 1);
+        assert_eq!(packed.differences(0, 3), vec![100]);
+        let reflections = find_reflections(&packed, 1);
+        let summary: Vec<(usize, usize)> = reflections
+            .iter()
+            .map(|reflection| (reflection.position, reflection.smudges()))
+            .collect();
+        assert_eq!(summary, vec![(1, 1), (2, 1), (3, 0)]);
+        assert_eq!(reflections[1].differences, vec![[(0, 100), (3, 100)]]);
+        // the three lines that match still mirror exactly
+        assert_eq!(find_reflections(&packed, 0).len(), 1);
+        assert_eq!(find_reflections(&packed, 0)[0].position, 3);
+    }
+}
@@ -0,0 +1,70 @@
+use day_14::{parse_script, render_ppm, Frame, Platform, Playback};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+fn caption(frame: &Frame) -> String {
+    let mut caption = match frame.side {
+        Some(side) => format!("run {}, tilt {:?}", frame.run, side),
+        None => "start".to_string(),
+    };
+    if let Some(repetition) = frame.repetition {
+        caption += &format!(
+            ": cycle found, same as after run {} (every {} runs)",
+            repetition.start, repetition.length
+        );
+    }
+    caption
+}
+
+// usage: animate <filepath> [tilt script] [runs] [--delay=ms] [--ppm=<dir>] [--scale=N]
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let contents = fs::read_to_string(&args[1]).expect("Something went wrong reading the file");
+    let mut script = "NWSE";
+    let mut runs = 1_000_000_000;
+    let mut delay = 100;
+    let mut ppm_dir: Option<&str> = None;
+    let mut scale = 4;
+    for arg in &args[2..] {
+        if let Some(ms) = arg.strip_prefix("--delay=") {
+            delay = ms
+                .parse()
+                .expect("delay should be a number of milliseconds");
+        } else if let Some(dir) = arg.strip_prefix("--ppm=") {
+            ppm_dir = Some(dir);
+        } else if let Some(pixels) = arg.strip_prefix("--scale=") {
+            scale = pixels.parse().expect("scale should be a number of pixels");
+        } else if let Ok(number) = arg.parse() {
+            runs = number;
+        } else {
+            script = arg;
+        }
+    }
+    let script = parse_script(script).unwrap_or_else(|err| panic!("{err}"));
+    let platform = Platform::parse(&contents).unwrap_or_else(|err| panic!("{err}"));
+
+    if let Some(dir) = ppm_dir {
+        fs::create_dir_all(dir).expect("Error creating the frame directory");
+    }
+    for (k, frame) in Playback::new(platform, &script, runs).enumerate() {
+        match ppm_dir {
+            Some(dir) => {
+                let path = Path::new(dir).join(format!("frame-{k:05}.ppm"));
+                let image = render_ppm(&frame.platform, scale, frame.repetition.is_some());
+                fs::write(&path, image).expect("Error writing image");
+                if frame.repetition.is_some() {
+                    println!("{}: {}", path.display(), caption(&frame));
+                }
+            }
+            None => {
+                // clear the screen and go back to the top left
+                print!("\x1b[2J\x1b[H");
+                println!("{}\n{}", caption(&frame), frame.platform);
+                thread::sleep(Duration::from_millis(delay));
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use day_14::{Platform, Side};
 use std::env;
 use std::fs;
 
@@ -5,66 +6,13 @@ fn main() {
     let args: Vec<String> = env::args().collect();
     let filename = &args[1];
     let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
-    let cfg = parse_input(&contents);
-    let cfg = transpose(cfg);
-    let cfg: Vec<Vec<char>> = cfg.into_iter().map(|v| roll_rocks(v)).collect();
-    let solution = cfg.into_iter().fold(0, |acc, col| acc + col_load(col));
+    let mut platform = Platform::parse(&contents).unwrap_or_else(|err| panic!("{err}"));
+    platform.tilt(Side::North);
+    let solution = platform.load(Side::North);
 
     println!("Solution: {:?}", solution);
 }
 
-fn roll_rocks(mut col: Vec<char>) -> Vec<char> {
-    let mut empty_loc = 0;
-
-    for i in 0..col.len() {
-        match col[i] {
-            '.' => continue,
-            '#' => empty_loc = i + 1,
-            'O' => {
-                col[i] = '.';
-                col[empty_loc] = 'O';
-                empty_loc += 1;
-            }
-            _ => {}
-        }
-    }
-    col
-}
-
-fn col_load(col: Vec<char>) -> usize {
-    let col_length = col.len();
-    col.into_iter().enumerate().fold(
-        0,
-        |acc, (i, c)| {
-            if c == 'O' {
-                acc + col_length - i
-            } else {
-                acc
-            }
-        },
-    )
-}
-
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|line| line.chars().collect()).collect()
-}
-
-fn transpose<T: Copy>(input: Vec<Vec<T>>) -> Vec<Vec<T>> {
-    let cols = input[0].len();
-    let rows = input.len();
-
-    (0..cols)
-        .map(|col| (0..rows).map(|row| input[row][col]).collect())
-        .collect()
-}
-
-fn cfg_to_string(cfg: &Vec<Vec<char>>) -> String {
-    cfg.iter()
-        .map(|row| row.iter().collect::<String>())
-        .collect::<Vec<String>>()
-        .join("\n")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,18 +45,16 @@ O..#.OO...
     #[test]
     fn test_parsing() {
         let input = get_input();
-        let parsed = parse_input(&input);
-        assert_eq!(cfg_to_string(&parsed), input);
+        let parsed = Platform::parse(input).unwrap();
+        assert_eq!(parsed.to_string(), input);
     }
     #[test]
     fn test_move_north() {
         let input = get_input();
-        let parsed = parse_input(&input);
-        let transposed = transpose(parsed);
-        let moved: Vec<Vec<char>> = transposed.into_iter().map(|v| roll_rocks(v)).collect();
-        let move_transposed = transpose(moved.clone());
-        assert_eq!(cfg_to_string(&move_transposed), moved_north());
-        let load = moved.into_iter().fold(0, |acc, col| acc + col_load(col));
+        let mut platform = Platform::parse(input).unwrap();
+        platform.tilt(Side::North);
+        assert_eq!(platform.to_string(), moved_north());
+        let load = platform.load(Side::North);
         assert_eq!(load, 136);
     }
 }
@@ -0,0 +1,441 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Side {
+    North,
+    West,
+    South,
+    East,
+}
+
+impl Side {
+    pub fn from_char(c: char) -> Option<Side> {
+        match c {
+            'N' => Some(Side::North),
+            'W' => Some(Side::West),
+            'S' => Some(Side::South),
+            'E' => Some(Side::East),
+            _ => None,
+        }
+    }
+}
+
+/// One letter per tilt, `N`, `W`, `S` or `E`, so a spin cycle is "NWSE".
+pub fn parse_script(script: &str) -> Result<Vec<Side>, String> {
+    script
+        .chars()
+        .map(|c| Side::from_char(c).ok_or_else(|| format!("{c:?} is not one of N, W, S or E")))
+        .collect()
+}
+
+/// The platform as one flat row-major grid of `O` (round rocks), `#` (cube
+/// rocks) and `.` (empty), tilted in place.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Platform {
+    pub rows: usize,
+    pub cols: usize,
+    cells: Vec<u8>,
+}
+
+impl Platform {
+    pub fn parse(input: &str) -> Result<Platform, String> {
+        let mut cells = Vec::with_capacity(input.len());
+        let mut rows = 0;
+        let cols = input.lines().next().map_or(0, |line| line.len());
+        if cols == 0 {
+            return Err("the platform is empty".to_string());
+        }
+        for (i, line) in input.lines().enumerate() {
+            if line.len() != cols {
+                return Err(format!(
+                    "row {} is {} long, expected {cols}",
+                    i + 1,
+                    line.len()
+                ));
+            }
+            if let Some(c) = line.chars().find(|c| !"O#.".contains(*c)) {
+                return Err(format!("unexpected {c:?} in row {}", i + 1));
+            }
+            cells.extend_from_slice(line.as_bytes());
+            rows += 1;
+        }
+        Ok(Platform { rows, cols, cells })
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> char {
+        self.cells[i * self.cols + j] as char
+    }
+
+    // the k-th cell of line `line`, counting from `side`. Lines run away from
+    // the side, so for north they are the columns.
+    fn index(&self, side: Side, line: usize, k: usize) -> usize {
+        match side {
+            Side::North => k * self.cols + line,
+            Side::South => (self.rows - 1 - k) * self.cols + line,
+            Side::West => line * self.cols + k,
+            Side::East => line * self.cols + self.cols - 1 - k,
+        }
+    }
+
+    fn lines_towards(&self, side: Side) -> (usize, usize) {
+        match side {
+            Side::North | Side::South => (self.cols, self.rows),
+            Side::West | Side::East => (self.rows, self.cols),
+        }
+    }
+
+    /// Rolls every round rock as far towards `side` as it goes.
+    pub fn tilt(&mut self, side: Side) {
+        let (lines, len) = self.lines_towards(side);
+        for line in 0..lines {
+            // where the next rock rolling this way stops
+            let mut free = 0;
+            for k in 0..len {
+                let index = self.index(side, line, k);
+                match self.cells[index] {
+                    b'#' => free = k + 1,
+                    b'O' => {
+                        self.cells[index] = b'.';
+                        let stop = self.index(side, line, free);
+                        self.cells[stop] = b'O';
+                        free += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    pub fn run_script(&mut self, script: &[Side]) {
+        for side in script {
+            self.tilt(*side);
+        }
+    }
+
+    /// Each round rock weighs as much as how many rows (or columns) it is
+    /// from the far edge, counting its own.
+    pub fn load(&self, side: Side) -> usize {
+        let (lines, len) = self.lines_towards(side);
+        let mut load = 0;
+        for line in 0..lines {
+            for k in 0..len {
+                if self.cells[self.index(side, line, k)] == b'O' {
+                    load += len - k;
+                }
+            }
+        }
+        load
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.cells.chunks(self.cols).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", String::from_utf8_lossy(row))?;
+        }
+        Ok(())
+    }
+}
+
+/// Where the platform starts repeating: after `start` runs of the script it
+/// is back where it was, every `length` runs.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Repetition {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Repetition {
+    /// The earlier run that leaves the platform as `runs` runs would.
+    pub fn equivalent(&self, runs: usize) -> usize {
+        if runs < self.start {
+            runs
+        } else {
+            (runs - self.start) % self.length + self.start
+        }
+    }
+}
+
+/// The platform after running the script `runs` times. Every state is
+/// hashed, so once one repeats the rest are skipped. Also gives the
+/// repetition if it was found before `runs` ran out.
+pub fn run_repeated(
+    mut platform: Platform,
+    script: &[Side],
+    runs: usize,
+) -> (Platform, Option<Repetition>) {
+    let mut seen: HashMap<Platform, usize> = HashMap::new();
+    let mut history = vec![platform.clone()];
+    seen.insert(platform.clone(), 0);
+    for run in 1..=runs {
+        platform.run_script(script);
+        if let Some(start) = seen.get(&platform) {
+            let repetition = Repetition {
+                start: *start,
+                length: run - start,
+            };
+            let platform = history.swap_remove(repetition.equivalent(runs));
+            return (platform, Some(repetition));
+        }
+        seen.insert(platform.clone(), run);
+        history.push(platform.clone());
+    }
+    (platform, None)
+}
+
+/// One tilt of an animation. The first frame is the platform before any
+/// tilt, with no `side` and `run` 0.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Frame {
+    pub run: usize,
+    pub side: Option<Side>,
+    pub platform: Platform,
+    /// Set on the last tilt of the run that brings back a platform seen
+    /// after an earlier run, which is where `run_repeated` stops.
+    pub repetition: Option<Repetition>,
+}
+
+/// Every tilt of the script run up to `runs` times, ending early at the
+/// first repetition since everything after it has been seen already.
+pub struct Playback<'a> {
+    platform: Platform,
+    script: &'a [Side],
+    runs: usize,
+    run: usize,
+    step: usize,
+    seen: HashMap<Platform, usize>,
+    done: bool,
+}
+
+impl<'a> Playback<'a> {
+    pub fn new(platform: Platform, script: &'a [Side], runs: usize) -> Playback<'a> {
+        Playback {
+            platform,
+            script,
+            runs,
+            run: 0,
+            step: 0,
+            seen: HashMap::new(),
+            done: false,
+        }
+    }
+}
+
+impl Iterator for Playback<'_> {
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Frame> {
+        if self.done {
+            return None;
+        }
+        if self.run == 0 {
+            self.seen.insert(self.platform.clone(), 0);
+            self.run = 1;
+            self.done = self.runs == 0 || self.script.is_empty();
+            return Some(Frame {
+                run: 0,
+                side: None,
+                platform: self.platform.clone(),
+                repetition: None,
+            });
+        }
+        let side = self.script[self.step];
+        self.platform.tilt(side);
+        let mut frame = Frame {
+            run: self.run,
+            side: Some(side),
+            platform: self.platform.clone(),
+            repetition: None,
+        };
+        self.step += 1;
+        if self.step == self.script.len() {
+            if let Some(start) = self.seen.get(&self.platform) {
+                frame.repetition = Some(Repetition {
+                    start: *start,
+                    length: self.run - start,
+                });
+                self.done = true;
+            } else {
+                self.seen.insert(self.platform.clone(), self.run);
+            }
+            self.done |= self.run == self.runs;
+            self.run += 1;
+            self.step = 0;
+        }
+        Some(frame)
+    }
+}
+
+const ROUND_COLOUR: [u8; 3] = [220, 220, 210];
+const CUBE_COLOUR: [u8; 3] = [110, 80, 60];
+const EMPTY_COLOUR: [u8; 3] = [20, 20, 30];
+const MARK_COLOUR: [u8; 3] = [220, 40, 40];
+
+/// The platform as a binary PPM, `scale` pixels a cell. With `marked` it
+/// gets a red border, for the frame where the cycle is found.
+pub fn render_ppm(platform: &Platform, scale: usize, marked: bool) -> Vec<u8> {
+    let scale = scale.max(1);
+    let border = if marked { scale.max(2) } else { 0 };
+    let width = platform.cols * scale + 2 * border;
+    let height = platform.rows * scale + 2 * border;
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    let header = image.len();
+    image.resize(header + width * height * 3, 0);
+    for (pixel, colour) in image[header..].chunks_mut(3).enumerate() {
+        let (y, x) = (pixel / width, pixel % width);
+        let inside =
+            (border..height - border).contains(&y) && (border..width - border).contains(&x);
+        let rgb = if !inside {
+            MARK_COLOUR
+        } else {
+            match platform.get((y - border) / scale, (x - border) / scale) {
+                'O' => ROUND_COLOUR,
+                '#' => CUBE_COLOUR,
+                _ => EMPTY_COLOUR,
+            }
+        };
+        colour.copy_from_slice(&rgb);
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Platform {
+        Platform::parse(
+            "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tilt_each_way() {
+        let mut platform = example();
+        platform.tilt(Side::North);
+        assert_eq!(
+            platform.to_string(),
+            "OOOO.#.O..
+OO..#....#
+OO..O##..O
+O..#.OO...
+........#.
+..#....#.#
+..O..#.O.O
+..O.......
+#....###..
+#....#...."
+        );
+        assert_eq!(platform.load(Side::North), 136);
+
+        let mut platform = Platform::parse("O.#.O\n.O..O").unwrap();
+        platform.tilt(Side::East);
+        assert_eq!(platform.to_string(), ".O#.O\n...OO");
+        platform.tilt(Side::South);
+        assert_eq!(platform.to_string(), "..#.O\n.O.OO");
+        platform.tilt(Side::West);
+        assert_eq!(platform.to_string(), "..#O.\nOOO..");
+        assert_eq!(platform.load(Side::North), 2 + 3);
+        assert_eq!(platform.load(Side::West), 2 + 5 + 4 + 3);
+        assert_eq!(platform.load(Side::East), 4 + 1 + 2 + 3);
+        assert_eq!(platform.load(Side::South), 1 + 2 + 2 + 2);
+    }
+
+    #[test]
+    fn test_billion_cycles() {
+        let script = parse_script("NWSE").unwrap();
+        let (platform, repetition) = run_repeated(example(), &script, 1_000_000_000);
+        assert_eq!(platform.load(Side::North), 64);
+        assert_eq!(
+            repetition,
+            Some(Repetition {
+                start: 3,
+                length: 7
+            })
+        );
+        // too few runs to find the repetition
+        let (platform, repetition) = run_repeated(example(), &script, 3);
+        assert_eq!(repetition, None);
+        assert_eq!(platform.load(Side::North), 69);
+    }
+
+    #[test]
+    fn test_other_scripts() {
+        // skipping ahead lands where running every repetition would
+        let script = parse_script("NNEW").unwrap();
+        let mut platform = example();
+        for _ in 0..50 {
+            platform.run_script(&script);
+        }
+        let (skipped, repetition) = run_repeated(example(), &script, 50);
+        assert!(repetition.is_some());
+        assert_eq!(skipped, platform);
+        assert!(parse_script("NWX").is_err());
+    }
+
+    #[test]
+    fn test_bad_input() {
+        assert!(Platform::parse("O.#\nO.").is_err());
+        assert!(Platform::parse("O.x").is_err());
+        assert!(Platform::parse("").is_err());
+        assert!(Platform::parse("\nO.").is_err());
+    }
+
+    #[test]
+    fn test_playback() {
+        let script = parse_script("NWSE").unwrap();
+        let frames: Vec<Frame> = Playback::new(example(), &script, 1_000_000_000).collect();
+        // the start, then four tilts a run up to the repetition at run 10
+        assert_eq!(frames.len(), 1 + 4 * 10);
+        assert_eq!(frames[0].platform, example());
+        assert_eq!(frames[1].side, Some(Side::North));
+        assert_eq!(frames[1].platform.load(Side::North), 136);
+        let marked: Vec<usize> = (0..frames.len())
+            .filter(|k| frames[*k].repetition.is_some())
+            .collect();
+        assert_eq!(marked, vec![40]);
+        let (_, repetition) = run_repeated(example(), &script, 1_000_000_000);
+        assert_eq!(frames[40].repetition, repetition);
+        assert_eq!(frames[40].run, 10);
+        assert_eq!(frames[40].platform, frames[12].platform);
+
+        // stops after the runs asked for
+        let frames: Vec<Frame> = Playback::new(example(), &script, 2).collect();
+        assert_eq!(frames.len(), 9);
+        assert_eq!(frames[8].repetition, None);
+    }
+
+    #[test]
+    fn test_render_ppm() {
+        let platform = Platform::parse("O#\n..").unwrap();
+        let image = render_ppm(&platform, 2, false);
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 4 * 4 * 3);
+        let pixel = |image: &[u8], y: usize, x: usize, width: usize| {
+            let offset = image.len() - (width * width - y * width - x) * 3;
+            [image[offset], image[offset + 1], image[offset + 2]]
+        };
+        assert_eq!(pixel(&image, 1, 1, 4), ROUND_COLOUR);
+        assert_eq!(pixel(&image, 0, 3, 4), CUBE_COLOUR);
+        assert_eq!(pixel(&image, 3, 0, 4), EMPTY_COLOUR);
+        // a two pixel border around the marked frame
+        let image = render_ppm(&platform, 2, true);
+        assert_eq!(pixel(&image, 0, 0, 8), MARK_COLOUR);
+        assert_eq!(pixel(&image, 2, 2, 8), ROUND_COLOUR);
+    }
+}
@@ -1,3 +1,4 @@
+use day_15::label_2_box;
 use std::env;
 use std::fs;
 
@@ -9,14 +10,8 @@ fn main() {
     let solution = get_solution(&seqs);
     println!("Solution: {:?}", solution);
 }
-fn string_hash(input: &str) -> usize {
-    input
-        .chars()
-        .fold(0, |acc, c| (17 * (acc + c as usize)) % 256)
-}
-
 fn get_solution(sequences: &Vec<&str>) -> usize {
-    sequences.iter().map(|s| string_hash(s)).sum()
+    sequences.iter().map(|s| label_2_box(s)).sum()
 }
 
 fn parse_input(input: &str) -> Vec<&str> {
@@ -24,12 +19,11 @@ fn parse_input(input: &str) -> Vec<&str> {
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     #[test]
     fn test_hash() {
-        assert_eq!(string_hash("HASH"), 52);
+        assert_eq!(label_2_box("HASH"), 52);
     }
 
     #[test]
@@ -0,0 +1,37 @@
+use day_15::{Repl, REPL_HELP};
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+// usage: repl [filepath], the file's steps can then be run one at a time
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let mut repl = match args.get(1) {
+        Some(filename) => {
+            let contents =
+                fs::read_to_string(filename).expect("Something went wrong reading the file");
+            let repl = Repl::with_steps(&contents).unwrap_or_else(|err| panic!("{err}"));
+            println!("{} steps to run", repl.steps_left());
+            repl
+        }
+        None => Repl::new(),
+    };
+    println!("{REPL_HELP}\nquit              leave the repl");
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush().expect("Error writing the prompt");
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+        if line.trim() == "quit" {
+            break;
+        }
+        match repl.execute(&line) {
+            Ok(shown) => println!("{shown}"),
+            Err(message) => println!("error: {message}"),
+        }
+    }
+}
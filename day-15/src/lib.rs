@@ -0,0 +1,448 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::Index;
+
+pub const NUM_BOXES: usize = 256;
+
+/// The HASH algorithm, which picks the box a label goes in.
+pub fn label_2_box(input: &str) -> usize {
+    input
+        .chars()
+        .fold(0, |acc, c| (17 * (acc + c as usize)) % 256)
+}
+
+/// One step of the initialization sequence.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Step<'a> {
+    // label=N
+    Insert(&'a str, usize),
+    // label-
+    Remove(&'a str),
+}
+
+impl<'a> Step<'a> {
+    pub fn parse(input: &'a str) -> Result<Step<'a>, String> {
+        let input = input.trim();
+        let step = match input.split_once('=') {
+            Some((label, focal_length)) => match focal_length.parse() {
+                Ok(focal_length) => Step::Insert(label, focal_length),
+                Err(_) => return Err(format!("{focal_length:?} is not a focal length")),
+            },
+            None => match input.strip_suffix('-') {
+                Some(label) => Step::Remove(label),
+                None => return Err(format!("{input:?} should be label=N or label-")),
+            },
+        };
+        let label = step.label();
+        if label.is_empty() || label.contains(['=', '-']) {
+            return Err(format!("{label:?} in {input:?} is not a label"));
+        }
+        Ok(step)
+    }
+
+    pub fn label(&self) -> &'a str {
+        match self {
+            Step::Insert(label, _) | Step::Remove(label) => label,
+        }
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<Step<'_>>, String> {
+    input.trim().split(',').map(Step::parse).collect()
+}
+
+// (label, focal length) front to back
+type Lenses = Vec<(String, usize)>;
+
+/// Lenses by label, kept in the box their label hashes to and in the order
+/// they went in.
+#[derive(PartialEq, Clone)]
+pub struct LensLibrary {
+    boxes: Vec<Lenses>,
+}
+
+impl Default for LensLibrary {
+    fn default() -> Self {
+        LensLibrary::new()
+    }
+}
+
+impl LensLibrary {
+    pub fn new() -> LensLibrary {
+        LensLibrary {
+            boxes: vec![vec![]; NUM_BOXES],
+        }
+    }
+
+    fn slot(&self, label: &str) -> (usize, Option<usize>) {
+        let box_id = label_2_box(label);
+        let slot = self.boxes[box_id].iter().position(|(x, _)| x == label);
+        (box_id, slot)
+    }
+
+    /// Puts the lens in the back of its box, or swaps it for the lens with
+    /// the same label where that one is. Gives the old focal length.
+    pub fn insert(&mut self, label: &str, focal_length: usize) -> Option<usize> {
+        match self.slot(label) {
+            (box_id, Some(slot)) => Some(std::mem::replace(
+                &mut self.boxes[box_id][slot].1,
+                focal_length,
+            )),
+            (box_id, None) => {
+                self.boxes[box_id].push((label.to_string(), focal_length));
+                None
+            }
+        }
+    }
+
+    /// Takes the lens out, moving the ones behind it forward.
+    pub fn remove(&mut self, label: &str) -> Option<usize> {
+        let (box_id, slot) = self.slot(label);
+        slot.map(|slot| self.boxes[box_id].remove(slot).1)
+    }
+
+    pub fn get(&self, label: &str) -> Option<usize> {
+        let (box_id, slot) = self.slot(label);
+        slot.map(|slot| self.boxes[box_id][slot].1)
+    }
+
+    pub fn apply(&mut self, step: Step) {
+        match step {
+            Step::Insert(label, focal_length) => {
+                self.insert(label, focal_length);
+            }
+            Step::Remove(label) => {
+                self.remove(label);
+            }
+        }
+    }
+
+    /// The lenses in box `box_id`, front first.
+    pub fn lenses(&self, box_id: usize) -> &[(String, usize)] {
+        &self.boxes[box_id]
+    }
+
+    /// One box like the puzzle prints it, `Box 3: [ot 7] [ab 5]`.
+    pub fn show_box(&self, box_id: usize) -> String {
+        let mut line = format!("Box {box_id}:");
+        if self.boxes[box_id].is_empty() {
+            line += " empty";
+        }
+        for (label, focal_length) in &self.boxes[box_id] {
+            line += &format!(" [{label} {focal_length}]");
+        }
+        line
+    }
+
+    pub fn len(&self) -> usize {
+        self.boxes.iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boxes.iter().all(Vec::is_empty)
+    }
+
+    /// Every lens in box order, then front to back.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> + '_ {
+        self.boxes
+            .iter()
+            .flatten()
+            .map(|(label, focal_length)| (label.as_str(), *focal_length))
+    }
+
+    pub fn focusing_power(&self) -> usize {
+        self.boxes
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (box_id, lenses)| {
+                acc + lenses
+                    .iter()
+                    .enumerate()
+                    .fold(0, |acc_inner, (slot, (_, focal_length))| {
+                        acc_inner + (1 + box_id) * (1 + slot) * focal_length
+                    })
+            })
+    }
+}
+
+/// Prints the non-empty boxes like the puzzle, `Box 0: [rn 1] [cm 2]`.
+impl fmt::Debug for LensLibrary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (box_id, lenses) in self.boxes.iter().enumerate() {
+            if !lenses.is_empty() {
+                writeln!(f, "{}", self.show_box(box_id))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Index<&str> for LensLibrary {
+    type Output = usize;
+
+    fn index(&self, label: &str) -> &usize {
+        let (box_id, slot) = self.slot(label);
+        match slot {
+            Some(slot) => &self.boxes[box_id][slot].1,
+            None => panic!("no lens labelled {label:?}"),
+        }
+    }
+}
+
+impl<'a> Extend<Step<'a>> for LensLibrary {
+    fn extend<I: IntoIterator<Item = Step<'a>>>(&mut self, steps: I) {
+        for step in steps {
+            self.apply(step);
+        }
+    }
+}
+
+impl<'a> Extend<(&'a str, usize)> for LensLibrary {
+    fn extend<I: IntoIterator<Item = (&'a str, usize)>>(&mut self, lenses: I) {
+        for (label, focal_length) in lenses {
+            self.insert(label, focal_length);
+        }
+    }
+}
+
+impl<'a> FromIterator<Step<'a>> for LensLibrary {
+    fn from_iter<I: IntoIterator<Item = Step<'a>>>(steps: I) -> Self {
+        let mut library = LensLibrary::new();
+        library.extend(steps);
+        library
+    }
+}
+
+impl<'a> FromIterator<(&'a str, usize)> for LensLibrary {
+    fn from_iter<I: IntoIterator<Item = (&'a str, usize)>>(lenses: I) -> Self {
+        let mut library = LensLibrary::new();
+        library.extend(lenses);
+        library
+    }
+}
+
+impl IntoIterator for LensLibrary {
+    type Item = (String, usize);
+    type IntoIter = std::iter::Flatten<std::vec::IntoIter<Lenses>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.boxes.into_iter().flatten()
+    }
+}
+
+impl<'a> IntoIterator for &'a LensLibrary {
+    type Item = (&'a str, usize);
+    type IntoIter = Box<dyn Iterator<Item = (&'a str, usize)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+pub const REPL_HELP: &str = "\
+label=N, label-   run steps, several can be separated by commas
+next (or enter)   run the next step from the file
+run               run every step left from the file
+undo              undo the last step
+boxes             show every box with lenses in it
+power             show the focusing power
+help              show this list";
+
+/// Runs steps one at a time, typed in or taken from a file, and remembers
+/// enough to undo them.
+#[derive(Default)]
+pub struct Repl {
+    library: LensLibrary,
+    // each step run, and what was in the box it changed before
+    history: Vec<(String, usize, Lenses)>,
+    pending: VecDeque<String>,
+}
+
+impl Repl {
+    pub fn new() -> Repl {
+        Repl::default()
+    }
+
+    /// A session that can step through `input`, a whole initialization
+    /// sequence.
+    pub fn with_steps(input: &str) -> Result<Repl, String> {
+        parse_input(input)?;
+        let mut repl = Repl::new();
+        repl.pending = input.trim().split(',').map(String::from).collect();
+        Ok(repl)
+    }
+
+    pub fn library(&self) -> &LensLibrary {
+        &self.library
+    }
+
+    pub fn steps_left(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Runs one step and shows the box it went to.
+    pub fn step(&mut self, step: Step) -> String {
+        let box_id = label_2_box(step.label());
+        let before = self.library.boxes[box_id].clone();
+        self.library.apply(step);
+        let text = match step {
+            Step::Insert(label, focal_length) => format!("{label}={focal_length}"),
+            Step::Remove(label) => format!("{label}-"),
+        };
+        let shown = format!("{text} (HASH {box_id}): {}", self.library.show_box(box_id));
+        self.history.push((text, box_id, before));
+        shown
+    }
+
+    pub fn undo(&mut self) -> Option<String> {
+        let (text, box_id, before) = self.history.pop()?;
+        self.library.boxes[box_id] = before;
+        Some(format!("undid {text}: {}", self.library.show_box(box_id)))
+    }
+
+    fn next_step(&mut self) -> Result<String, String> {
+        let step = self.pending.pop_front().ok_or("no steps left")?;
+        Ok(self.step(Step::parse(&step)?))
+    }
+
+    /// Runs one line of input, see `REPL_HELP`.
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        match line.trim() {
+            "" | "next" => self.next_step(),
+            "run" => {
+                let mut shown = Vec::new();
+                while !self.pending.is_empty() {
+                    shown.push(self.next_step()?);
+                }
+                shown.push(format!("{:?}", self.library).trim_end().to_string());
+                Ok(shown.join("\n"))
+            }
+            "undo" => self.undo().ok_or_else(|| "nothing to undo".to_string()),
+            "boxes" if self.library.is_empty() => Ok("every box is empty".to_string()),
+            "boxes" => Ok(format!("{:?}", self.library).trim_end().to_string()),
+            "power" => Ok(format!("focusing power {}", self.library.focusing_power())),
+            "help" => Ok(REPL_HELP.to_string()),
+            steps => {
+                let steps = parse_input(steps)?;
+                let shown: Vec<String> = steps.into_iter().map(|step| self.step(step)).collect();
+                Ok(shown.join("\n"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+    #[test]
+    fn test_steps() {
+        assert_eq!(Step::parse("rn=1"), Ok(Step::Insert("rn", 1)));
+        assert_eq!(Step::parse("cm-"), Ok(Step::Remove("cm")));
+        assert_eq!(Step::parse("qp=3\n"), Ok(Step::Insert("qp", 3)));
+        assert!(Step::parse("qp").is_err());
+        assert!(Step::parse("qp=x").is_err());
+        assert!(Step::parse("-").is_err());
+        assert!(Step::parse("=5").is_err());
+        assert!(Step::parse("ab=cd-").is_err());
+        assert!(Step::parse("ab-cd=5").is_err());
+        assert!(Step::parse("ab--").is_err());
+        assert_eq!(parse_input(EXAMPLE).unwrap().len(), 11);
+    }
+
+    #[test]
+    fn test_library() {
+        let library: LensLibrary = parse_input(EXAMPLE).unwrap().into_iter().collect();
+        assert_eq!(
+            format!("{library:?}"),
+            "Box 0: [rn 1] [cm 2]\nBox 3: [ot 7] [ab 5] [pc 6]\n"
+        );
+        assert_eq!(library.focusing_power(), 145);
+        assert_eq!(library["ot"], 7);
+        assert_eq!(library.get("qp"), None);
+        assert_eq!(library.len(), 5);
+        let labels: Vec<&str> = library.iter().map(|(label, _)| label).collect();
+        assert_eq!(labels, vec!["rn", "cm", "ot", "ab", "pc"]);
+        let owned: Vec<(String, usize)> = library.clone().into_iter().collect();
+        assert_eq!(owned[2], ("ot".to_string(), 7));
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut library: LensLibrary = [("rn", 1), ("cm", 2)].into_iter().collect();
+        assert_eq!(library.insert("rn", 4), Some(1));
+        // replacing keeps the lens where it was
+        assert_eq!(
+            library.lenses(0),
+            &[("rn".to_string(), 4), ("cm".to_string(), 2)]
+        );
+        assert_eq!(library.remove("rn"), Some(4));
+        assert_eq!(library.remove("rn"), None);
+        library.extend([Step::Remove("cm")]);
+        assert!(library.is_empty());
+        assert_eq!(format!("{library:?}"), "");
+    }
+
+    #[test]
+    fn test_repl() {
+        let mut repl = Repl::with_steps(EXAMPLE).unwrap();
+        assert_eq!(
+            repl.execute(""),
+            Ok("rn=1 (HASH 0): Box 0: [rn 1]".to_string())
+        );
+        assert_eq!(
+            repl.execute("next"),
+            Ok("cm- (HASH 0): Box 0: [rn 1]".to_string())
+        );
+        assert_eq!(repl.steps_left(), 9);
+        assert_eq!(
+            repl.execute("undo"),
+            Ok("undid cm-: Box 0: [rn 1]".to_string())
+        );
+        assert_eq!(
+            repl.execute("rn=5,ab=2"),
+            Ok("rn=5 (HASH 0): Box 0: [rn 5]\nab=2 (HASH 3): Box 3: [ab 2]".to_string())
+        );
+        repl.execute("undo").unwrap();
+        assert_eq!(
+            repl.execute("undo"),
+            Ok("undid rn=5: Box 0: [rn 1]".to_string())
+        );
+        assert_eq!(
+            repl.execute("undo"),
+            Ok("undid rn=1: Box 0: empty".to_string())
+        );
+        assert_eq!(repl.execute("undo"), Err("nothing to undo".to_string()));
+        assert!(repl.execute("rn").is_err());
+        assert_eq!(repl.execute("boxes"), Ok("every box is empty".to_string()));
+        // undoing doesn't put steps back in the file
+        let shown = repl.execute("run").unwrap();
+        assert!(shown.ends_with("Box 0: [cm 2]\nBox 3: [ot 7] [ab 5] [pc 6]"));
+        assert_eq!(repl.execute("power"), Ok("focusing power 142".to_string()));
+        assert_eq!(repl.execute(""), Err("no steps left".to_string()));
+    }
+
+    #[test]
+    fn test_repl_help_matches_commands() {
+        // the first line is the steps themselves, the rest name a command each
+        let commands: Vec<&str> = REPL_HELP
+            .lines()
+            .skip(1)
+            .map(|line| line.split_whitespace().next().unwrap())
+            .collect();
+        assert_eq!(
+            commands,
+            vec!["next", "run", "undo", "boxes", "power", "help"]
+        );
+        for command in commands {
+            // anything execute doesn't know is read as a step, which these aren't
+            let mut repl = Repl::with_steps(EXAMPLE).unwrap();
+            let as_step = parse_input(command).unwrap_err();
+            assert_ne!(repl.execute(command), Err(as_step), "{command}");
+        }
+        assert_eq!(Repl::new().execute("help"), Ok(REPL_HELP.to_string()));
+        assert!(Repl::new().execute("quit").is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use day_16::{edge_energies, energise, render_heat_map, render_overlay, render_table, Edge, Grid};
+use std::env;
+use std::fs;
+
+// usage: energy <filepath> [top|bottom|left|right <position>] [--plain] [--table]
+// shows the tiles lit from the given start (by default the top left going
+// right), then how every start along the edges compares
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let filename = &args[1];
+    let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
+    let grid = Grid::new(&contents);
+    let colour = !args.iter().any(|arg| arg == "--plain");
+    let table = args.iter().any(|arg| arg == "--table");
+    let start = args[2..]
+        .iter()
+        .position(|arg| !arg.starts_with("--"))
+        .map(|k| {
+            let edge = match args[2 + k].as_str() {
+                "top" => Edge::Top,
+                "bottom" => Edge::Bottom,
+                "left" => Edge::Left,
+                "right" => Edge::Right,
+                other => panic!("{other:?} should be top, bottom, left or right"),
+            };
+            let position: usize = args
+                .get(3 + k)
+                .and_then(|position| position.parse().ok())
+                .expect("the edge should be followed by a position");
+            (edge, position)
+        })
+        .unwrap_or((Edge::Left, 0));
+
+    let energies = edge_energies(&grid);
+    let Some(chosen) = energies
+        .iter()
+        .find(|energy| (energy.edge, energy.position) == start)
+    else {
+        println!("no start at {:?} {}", start.0, start.1);
+        return;
+    };
+    let energised = energise(&grid, chosen.start);
+    println!(
+        "{:?} {}: {} energised",
+        chosen.edge,
+        chosen.position,
+        energised.len()
+    );
+    print!("{}", render_overlay(&grid, &energised, colour));
+    println!();
+    if table {
+        print!("{}", render_table(&energies));
+    } else {
+        print!("{}", render_heat_map(&energies));
+    }
+}
@@ -0,0 +1,453 @@
+use rayon::prelude::*;
+use std::collections::{HashSet, VecDeque};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Point {
+    pub i: i32,
+    pub j: i32,
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Movement {
+    pub del_i: i32,
+    pub del_j: i32,
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Part {
+    MirrorBack,      //backslash
+    MirrorFront,     // forward slash
+    SplitHorizontal, // |
+    SplitVertical,   // -
+}
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct State {
+    pub point: Point,
+    pub movement: Movement,
+}
+
+pub struct Grid {
+    pub grid: Vec<Vec<Option<Part>>>,
+}
+impl Grid {
+    pub fn new(input: &str) -> Self {
+        let mut rows = Vec::new();
+        for line in input.lines() {
+            let mut row = Vec::new();
+            for c in line.chars() {
+                let part = match c {
+                    '/' => Some(Part::MirrorBack),
+                    '\\' => Some(Part::MirrorFront),
+                    '|' => Some(Part::SplitHorizontal),
+                    '-' => Some(Part::SplitVertical),
+                    _ => None,
+                };
+                row.push(part);
+            }
+            rows.push(row);
+        }
+        Grid { grid: rows }
+    }
+    fn is_valid_state(&self, state: &State) -> bool {
+        let i = state.point.i;
+        let j = state.point.j;
+        if i < 0 || j < 0 {
+            return false;
+        }
+        let num_rows = self.grid.len();
+        if i as usize >= num_rows {
+            return false;
+        }
+        let num_cols = self.grid[i as usize].len();
+        if j as usize >= num_cols {
+            return false;
+        }
+        true
+    }
+    fn next_state(&self, state: State) -> Vec<State> {
+        let part = &self.grid[state.point.i as usize][state.point.j as usize];
+        match part {
+            Some(part) => part
+                .next_state(&state)
+                .into_iter()
+                .filter(|s| self.is_valid_state(s))
+                .collect::<Vec<State>>(),
+            None => {
+                let next_point = Point {
+                    i: state.point.i + state.movement.del_i,
+                    j: state.point.j + state.movement.del_j,
+                };
+                let next_state = State {
+                    point: next_point,
+                    movement: state.movement,
+                };
+                if self.is_valid_state(&next_state) {
+                    vec![next_state]
+                } else {
+                    vec![]
+                }
+            }
+        }
+    }
+    /// Every way into the grid, from each edge tile heading away from that
+    /// edge.
+    pub fn possible_starts(&self) -> Vec<State> {
+        let mut start_states = Vec::new();
+        // top row and bottom row
+        for j in 0..self.grid[0].len() {
+            for (i, del_i) in [0, self.grid.len() - 1].iter().zip([1, -1].iter()) {
+                let start_state = State {
+                    point: Point {
+                        i: *i as i32,
+                        j: j as i32,
+                    },
+                    movement: Movement {
+                        del_i: *del_i,
+                        del_j: 0,
+                    },
+                };
+                start_states.push(start_state);
+            }
+        }
+        // left column and right column
+        for i in 0..self.grid.len() {
+            for (j, del_j) in [0, self.grid[0].len() - 1].iter().zip([1, -1].iter()) {
+                let start_state = State {
+                    point: Point {
+                        i: i as i32,
+                        j: *j as i32,
+                    },
+                    movement: Movement {
+                        del_i: 0,
+                        del_j: *del_j,
+                    },
+                };
+                start_states.push(start_state);
+            }
+        }
+
+        start_states
+    }
+}
+impl Part {
+    fn next_state(&self, state: &State) -> Vec<State> {
+        match self {
+            Part::MirrorBack => {
+                let new_direction = Movement {
+                    del_i: -state.movement.del_j,
+                    del_j: -state.movement.del_i,
+                };
+                let new_point = Point {
+                    i: state.point.i + new_direction.del_i,
+                    j: state.point.j + new_direction.del_j,
+                };
+                vec![State {
+                    point: new_point,
+                    movement: new_direction,
+                }]
+            }
+            Part::MirrorFront => {
+                let new_direction = Movement {
+                    del_i: state.movement.del_j,
+                    del_j: state.movement.del_i,
+                };
+                let new_point = Point {
+                    i: state.point.i + new_direction.del_i,
+                    j: state.point.j + new_direction.del_j,
+                };
+                vec![State {
+                    point: new_point,
+                    movement: new_direction,
+                }]
+            }
+            Part::SplitHorizontal => {
+                if state.movement.del_j != 0 {
+                    //split when light moving horizontally
+                    let mut new_states = Vec::new();
+                    for del_i in [-1, 1] {
+                        let new_direction = Movement { del_i, del_j: 0 };
+                        let new_point = Point {
+                            i: state.point.i + new_direction.del_i,
+                            j: state.point.j + new_direction.del_j,
+                        };
+                        new_states.push(State {
+                            point: new_point,
+                            movement: new_direction,
+                        });
+                    }
+                    new_states
+                } else {
+                    self.keep_going(state)
+                }
+            }
+            Part::SplitVertical => {
+                if state.movement.del_i != 0 {
+                    //split when light moving vertically
+                    let mut new_states = Vec::new();
+                    for del_j in [-1, 1] {
+                        let new_direction = Movement { del_i: 0, del_j };
+                        let new_point = Point {
+                            i: state.point.i + new_direction.del_i,
+                            j: state.point.j + new_direction.del_j,
+                        };
+                        new_states.push(State {
+                            point: new_point,
+                            movement: new_direction,
+                        });
+                    }
+                    new_states
+                } else {
+                    self.keep_going(state)
+                }
+            }
+        }
+    }
+
+    fn keep_going(&self, state: &State) -> Vec<State> {
+        let new_point = Point {
+            i: state.point.i + state.movement.del_i,
+            j: state.point.j + state.movement.del_j,
+        };
+        vec![State {
+            point: new_point,
+            movement: state.movement,
+        }]
+    }
+}
+
+/// Every tile the beam passes through, starting from `start_state`.
+pub fn energise(grid: &Grid, start_state: State) -> HashSet<Point> {
+    let mut seen_points: HashSet<Point> = HashSet::new();
+    let mut seen_states: HashSet<State> = HashSet::new();
+    let mut queue: VecDeque<State> = VecDeque::new();
+    queue.push_back(start_state);
+
+    while let Some(state) = queue.pop_front() {
+        if !seen_states.insert(state) {
+            continue;
+        }
+        seen_points.insert(state.point);
+
+        let next_states = grid.next_state(state);
+        for next_state in next_states {
+            queue.push_back(next_state);
+        }
+    }
+    seen_points
+}
+
+impl Part {
+    pub fn symbol(&self) -> char {
+        match self {
+            Part::MirrorBack => '/',
+            Part::MirrorFront => '\\',
+            Part::SplitHorizontal => '|',
+            Part::SplitVertical => '-',
+        }
+    }
+}
+
+/// The grid with the energised tiles picked out, in colour or with every
+/// energised tile as `#` like the puzzle.
+pub fn render_overlay(grid: &Grid, energised: &HashSet<Point>, colour: bool) -> String {
+    let mut output = String::new();
+    for (i, row) in grid.grid.iter().enumerate() {
+        for (j, part) in row.iter().enumerate() {
+            let lit = energised.contains(&Point {
+                i: i as i32,
+                j: j as i32,
+            });
+            let symbol = match part {
+                _ if lit && !colour => '#',
+                Some(part) => part.symbol(),
+                None => '.',
+            };
+            if colour && lit {
+                output.push_str("\x1b[30;43m");
+                output.push(symbol);
+                output.push_str("\x1b[0m");
+            } else {
+                output.push(symbol);
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// How many tiles a beam coming in at `position` along `edge` (the column
+/// for top and bottom, the row for left and right) energises.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EdgeEnergy {
+    pub edge: Edge,
+    pub position: usize,
+    pub start: State,
+    pub energised: usize,
+}
+
+impl EdgeEnergy {
+    pub fn new(start: State, energised: usize) -> EdgeEnergy {
+        let (edge, position) = match (start.movement.del_i, start.movement.del_j) {
+            (1, _) => (Edge::Top, start.point.j),
+            (-1, _) => (Edge::Bottom, start.point.j),
+            (_, 1) => (Edge::Left, start.point.i),
+            _ => (Edge::Right, start.point.i),
+        };
+        EdgeEnergy {
+            edge,
+            position: position as usize,
+            start,
+            energised,
+        }
+    }
+}
+
+/// Energy for every start from `possible_starts`, sorted by edge and then
+/// position.
+pub fn edge_energies(grid: &Grid) -> Vec<EdgeEnergy> {
+    let mut energies: Vec<EdgeEnergy> = grid
+        .possible_starts()
+        .into_par_iter()
+        .map(|start| EdgeEnergy::new(start, energise(grid, start).len()))
+        .collect();
+    energies.sort_by_key(|energy| (energy.edge as usize, energy.position));
+    energies
+}
+
+pub fn best_start(energies: &[EdgeEnergy]) -> Option<&EdgeEnergy> {
+    // the first of any ties
+    energies.iter().rev().max_by_key(|energy| energy.energised)
+}
+
+/// One line per start: edge, position and tiles energised, with
+/// `best_start` marked.
+pub fn render_table(energies: &[EdgeEnergy]) -> String {
+    let best = best_start(energies);
+    let mut output = String::from("edge    position  energised\n");
+    for energy in energies {
+        let mark = if Some(energy) == best {
+            " *"
+        } else {
+            ""
+        };
+        output += &format!(
+            "{:<8}{:>8}{:>11}{mark}\n",
+            format!("{:?}", energy.edge),
+            energy.position,
+            energy.energised
+        );
+    }
+    output
+}
+
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Each edge as a strip of shades, darker where starting there energises
+/// more, from the least to the most of any start.
+pub fn render_heat_map(energies: &[EdgeEnergy]) -> String {
+    let low = energies
+        .iter()
+        .map(|energy| energy.energised)
+        .min()
+        .unwrap_or(0);
+    let high = energies
+        .iter()
+        .map(|energy| energy.energised)
+        .max()
+        .unwrap_or(0);
+    let mut output = format!("{low} ' ' to {high} '█'\n");
+    for edge in [Edge::Top, Edge::Bottom, Edge::Left, Edge::Right] {
+        output += &format!("{:<7}", format!("{edge:?}"));
+        for energy in energies.iter().filter(|energy| energy.edge == edge) {
+            let shade = (energy.energised - low) * (SHADES.len() - 1) / (high - low).max(1);
+            output.push(SHADES[shade]);
+        }
+        output.push('\n');
+    }
+    if let Some(best) = best_start(energies) {
+        output += &format!(
+            "best: {:?} {} energises {}\n",
+            best.edge, best.position, best.energised
+        );
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_grid() -> Grid {
+        Grid::new(
+            r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....",
+        )
+    }
+
+    fn top_left() -> State {
+        State {
+            point: Point { i: 0, j: 0 },
+            movement: Movement { del_i: 0, del_j: 1 },
+        }
+    }
+
+    #[test]
+    fn test_energised_overlay() {
+        let grid = example_grid();
+        let energised = energise(&grid, top_left());
+        assert_eq!(energised.len(), 46);
+        let overlay = render_overlay(&grid, &energised, false);
+        // the puzzle's picture, with the parts the beam misses still drawn
+        let expected = r"######....
+|#-.\#....
+.#...#####
+.#...##.|.
+.#...##...
+.#...##..\
+.#..####..
+########..
+.#######.\
+.#//.#.#..
+";
+        assert_eq!(overlay, expected);
+        let coloured = render_overlay(&grid, &energised, true);
+        assert_eq!(coloured.matches("\x1b[30;43m").count(), 46);
+    }
+
+    #[test]
+    fn test_edge_energies() {
+        let grid = example_grid();
+        let energies = edge_energies(&grid);
+        assert_eq!(energies.len(), 40);
+        assert_eq!(energies[0].edge, Edge::Top);
+        assert_eq!(energies[20].edge, Edge::Left);
+        assert_eq!(energies[20].energised, 46);
+        let best = best_start(&energies).unwrap();
+        assert_eq!(
+            (best.edge, best.position, best.energised),
+            (Edge::Top, 3, 51)
+        );
+        let table = render_table(&energies);
+        assert_eq!(table.lines().count(), 41);
+        assert!(table.contains("Top            3         51 *\n"));
+        assert_eq!(table.matches(" *").count(), 1);
+        let heat_map = render_heat_map(&energies);
+        assert!(heat_map.ends_with("best: Top 3 energises 51\n"));
+        let top = heat_map.lines().nth(1).unwrap();
+        assert_eq!(top.chars().nth(7 + 3), Some('█'));
+    }
+}